@@ -0,0 +1,123 @@
+person-window-title = Person
+person-name = Name: { $name }
+person-age = Alter: { $age }
+person-location = Ort: { $location }
+person-color = Farbe: { $color }
+person-counter = Zähler: { $counter }
+person-language = Sprache
+person-edit-begin = Gemeinsam bearbeiten
+person-edit-begin-hint = Die folgenden Änderungen sammeln und alle auf einmal übernehmen
+person-edit-pending = { $count ->
+    [one] 1 ausstehende Änderung
+   *[other] { $count } ausstehende Änderungen
+}
+person-edit-commit = Übernehmen
+person-edit-abort = Verwerfen
+
+derived-age-group = Altersgruppe
+derived-color-name = Farbname
+derived-counter-parity = Zählerparität
+age-group-child = Kind
+age-group-teen = Jugendlich
+age-group-adult = Erwachsen
+age-group-senior = Senior
+parity-even = Gerade
+parity-odd = Ungerade
+color-black = Schwarz
+color-gray = Grau
+color-white = Weiß
+color-red = Rot
+color-orange = Orange
+color-yellow = Gelb
+color-green = Grün
+color-cyan = Türkis
+color-blue = Blau
+color-purple = Lila
+color-pink = Rosa
+color-brown = Braun
+
+button-change = Ändern
+button-grow-old = Älter werden
+button-randomize = Würfeln
+button-save = Speichern
+button-retry = Wiederholen
+button-cancel = Abbrechen
+
+dialog-name-title = Name
+dialog-location-title = Ort
+dialog-counter-title = Wird zu einer Zufallszahl addiert
+dialog-counter-action = Würfeln!
+dialog-presence = Bearbeitet gerade auch: { $users }
+dialog-name-hint = Vollständiger Name, bis zu { $max } Zeichen
+dialog-location-hint = Stadt oder Land, bis zu { $max } Zeichen
+dialog-invalid-length = Höchstens { $max } Zeichen
+dialog-invalid-range = Muss zwischen { $min } und { $max } liegen
+dialog-invalid-number = Muss eine ganze Zahl sein
+dialog-stale = Wurde geändert, während dieser Dialog offen war, zum Übernehmen erneut absenden
+
+color-picker-title = Farbe ändern
+color-picker-label = Was ist deine Farbe?
+color-picker-description = Wer du bist~
+
+palette-title = Befehle
+palette-placeholder = Befehl eingeben…
+palette-empty = Keine passenden Befehle
+
+event-ChangeName = Name ändern
+event-ChangeName-description = Die Person umbenennen
+event-GrowOlder = Älter werden
+event-GrowOlder-description = Das Alter der Person um ein Jahr erhöhen
+event-ChangeLocation = Ort ändern
+event-ChangeLocation-description = Die Person woanders hinziehen lassen
+event-OpenColorPicker = Farbe ändern
+event-OpenColorPicker-description = Eine neue Farbe für die Person wählen
+event-ChangeCounter = Zähler würfeln
+event-ChangeCounter-description = Eine Zahl und etwas Zufall zum Zähler addieren
+event-read-only = Nur-Lesen-Modus: Änderungen sind deaktiviert
+event-too-old = { $max } Jahre sind alt genug
+event-condition-failed = Verfügbarkeit konnte nicht geprüft werden: { $error }
+
+script-title = Skript
+script-run = Ausführen
+script-record = Aufnehmen
+script-stop = Aufnahme beenden
+
+history-title = Verlauf
+history-dock = Andocken:
+history-dock-right = Rechts
+history-dock-bottom = Unten
+history-dock-floating = Fenster
+history-export-csv = Als CSV exportieren
+history-export-json = Als JSON exportieren
+history-exported = Exportiert nach { $path }
+history-export-failed = Export fehlgeschlagen: { $error }
+history-empty = Noch keine Ereignisse
+history-no-change = Keine Änderung
+history-transaction = Transaktion: { $events }
+
+schedule-title = Zeitplan
+schedule-time = Zeit: { $time }s
+schedule-pause = Anhalten
+schedule-resume = Fortsetzen
+schedule-input = Eingabe
+schedule-delay = In
+schedule-repeat = Wiederholen
+schedule-add = Einplanen
+schedule-once = in { $remaining }s
+schedule-every = alle { $interval }s, nächstes in { $remaining }s
+schedule-empty = Nichts geplant
+
+rules-title = Regeln
+rules-reload = Neu laden
+rules-empty = Keine Regeln geladen
+rules-summary = Wenn { $when } dann { $then } ({ $fired } mal ausgelöst)
+rules-cycle = Durch Zyklus { $cycle } angehalten
+
+notify-handler-failed = { $event } fehlgeschlagen: { $error }
+notify-counter-changed = Zähler ist jetzt { $counter }
+notify-edit-failed = Änderungen nicht übernommen: { $reason }
+notify-event-stale = { $event } beruhte auf veralteten Werten, bitte prüfen und erneut absenden
+notify-shortcut-conflict = { $chord } für { $event } wurde ignoriert, es ist { $existing } zugeordnet
+notify-shortcut-reserved = { $chord } für { $event } wurde ignoriert, es ist reserviert
+notify-rule-cycle = Regel { $rule } deaktiviert, sie würde { $cycle } wiederholen
+notify-dialog-reloaded = { $path } neu geladen
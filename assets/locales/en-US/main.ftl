@@ -0,0 +1,126 @@
+person-window-title = Person
+person-name = Name: { $name }
+person-age = Age: { $age }
+person-location = Location: { $location }
+person-color = Color: { $color }
+person-counter = Counter: { $counter }
+person-language = Language
+person-edit-begin = Edit together
+person-edit-begin-hint = Collect the following changes and apply them all at once
+person-edit-pending = { $count ->
+    [one] 1 pending change
+   *[other] { $count } pending changes
+}
+person-edit-commit = Apply
+person-edit-abort = Discard
+
+derived-age-group = Age group
+derived-color-name = Color name
+derived-counter-parity = Counter parity
+age-group-child = Child
+age-group-teen = Teenager
+age-group-adult = Adult
+age-group-senior = Senior
+parity-even = Even
+parity-odd = Odd
+color-black = Black
+color-gray = Gray
+color-white = White
+color-red = Red
+color-orange = Orange
+color-yellow = Yellow
+color-green = Green
+color-cyan = Cyan
+color-blue = Blue
+color-purple = Purple
+color-pink = Pink
+color-brown = Brown
+
+button-change = Change
+button-grow-old = Grow old
+button-randomize = Randomize
+button-save = Save
+button-retry = Retry
+button-cancel = Cancel
+
+dialog-name-title = Name
+dialog-location-title = Location
+dialog-counter-title = This will be added to some random
+dialog-counter-action = Randomize!
+dialog-presence = Also editing: { $users }
+dialog-name-hint = Full name, up to { $max } characters
+dialog-location-hint = City or country, up to { $max } characters
+dialog-invalid-length = At most { $max } characters
+dialog-invalid-range = Must be between { $min } and { $max }
+dialog-invalid-number = Must be a whole number
+dialog-stale = Changed while this dialog was open, submit again to apply
+
+color-picker-title = Change Color
+color-picker-label = What is your color?
+color-picker-description = Who you is~
+
+palette-title = Commands
+palette-placeholder = Type a command…
+palette-empty = No matching commands
+
+event-ChangeName = Change name
+event-ChangeName-description = Rename the person
+event-GrowOlder = Grow older
+event-GrowOlder-description = Add one year to the person's age
+event-ChangeLocation = Change location
+event-ChangeLocation-description = Move the person somewhere else
+event-OpenColorPicker = Change color
+event-OpenColorPicker-description = Pick a new color for the person
+event-ChangeCounter = Randomize counter
+event-ChangeCounter-description = Add a number and some randomness to the counter
+event-read-only = Read-only mode: changes are disabled
+event-too-old = { $max } years is old enough
+event-condition-failed = Could not check availability: { $error }
+
+script-title = Script
+script-run = Run
+script-record = Record
+script-stop = Stop recording
+
+history-title = History
+history-dock = Dock:
+history-dock-right = Right
+history-dock-bottom = Bottom
+history-dock-floating = Window
+history-export-csv = Export CSV
+history-export-json = Export JSON
+history-exported = Exported to { $path }
+history-export-failed = Could not export: { $error }
+history-empty = No events yet
+history-no-change = No change
+history-transaction = Transaction: { $events }
+
+schedule-title = Schedule
+schedule-time = Time: { $time }s
+schedule-pause = Pause
+schedule-resume = Resume
+schedule-input = Input
+schedule-delay = In
+schedule-repeat = Repeat
+schedule-add = Schedule
+schedule-once = in { $remaining }s
+schedule-every = every { $interval }s, next in { $remaining }s
+schedule-empty = Nothing scheduled
+
+rules-title = Rules
+rules-reload = Reload
+rules-empty = No rules loaded
+rules-summary = When { $when } then { $then } (fired { $fired } { $fired ->
+    [one] time
+   *[other] times
+})
+rules-cycle = Stopped by cycle { $cycle }
+
+notify-handler-failed = { $event } failed: { $error }
+notify-counter-changed = Counter is now { $counter }
+notify-edit-failed = Changes not applied: { $reason }
+notify-event-stale = { $event } was based on outdated values, please check and submit again
+notify-shortcut-conflict = { $chord } for { $event } was ignored, it is bound to { $existing }
+notify-shortcut-reserved = { $chord } for { $event } was ignored, it is reserved
+notify-rule-cycle = Rule { $rule } disabled, it would repeat { $cycle }
+notify-dialog-reloaded = Reloaded { $path }
@@ -0,0 +1,262 @@
+//! Relay for `ReplicationPlugin`. Numbers every line it receives and broadcasts it to all
+//! connected instances, and replays the history to instances that join later. Each instance is
+//! written to from its own thread; one that falls too far behind is disconnected.
+//!
+//! Usage: `relay [address]`, listening on `127.0.0.1:7879` by default.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, TrySendError},
+    },
+    thread,
+};
+
+use serde_json::{Value, json};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7879";
+
+/// Lines queued for a client before it counts as too slow and is dropped.
+const CLIENT_QUEUE: usize = 1024;
+
+/// The history is compacted once it grows beyond this many frames.
+const MAX_HISTORY: usize = 1000;
+
+struct Client {
+    /// Lines for the client's writer thread, so a slow client never blocks the relay.
+    sender: mpsc::SyncSender<Arc<str>>,
+    stream: TcpStream,
+}
+
+/// What compaction needs to know about a broadcast frame.
+enum FrameKind {
+    /// Only the latest presence of each connected client matters.
+    Presence {
+        client: u64,
+    },
+    /// Only matters while a later edit hasn't overwritten all of these fields.
+    Edit {
+        fields: Vec<String>,
+    },
+    Other,
+}
+
+struct Frame {
+    kind: FrameKind,
+    line: Arc<str>,
+}
+
+impl Frame {
+    fn new(frame: &Value) -> Self {
+        let message = &frame["message"];
+        let kind = match message["type"].as_str() {
+            Some("presence") => FrameKind::Presence {
+                client: frame["client"].as_u64().unwrap_or_default(),
+            },
+            Some("edit") => FrameKind::Edit {
+                fields: message["changes"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|change| change[0].to_string())
+                    .collect(),
+            },
+            _ => FrameKind::Other,
+        };
+        Frame {
+            kind,
+            line: frame.to_string().into(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Relay {
+    next_seq: u64,
+    next_client: u64,
+    clients: HashMap<u64, Client>,
+    history: Vec<Frame>,
+}
+
+impl Relay {
+    fn join(&mut self, stream: TcpStream) -> io::Result<u64> {
+        let client = self.next_client;
+        self.next_client += 1;
+
+        let writer = stream.try_clone()?;
+        let (sender, receiver) = mpsc::sync_channel(CLIENT_QUEUE);
+        let welcome: Arc<str> = json!({ "type": "welcome", "client": client })
+            .to_string()
+            .into();
+        let history: Vec<Arc<str>> = self
+            .history
+            .iter()
+            .map(|frame| frame.line.clone())
+            .collect();
+        thread::spawn(move || {
+            write_lines(writer, [welcome].into_iter().chain(history).chain(receiver))
+        });
+
+        self.clients.insert(client, Client { sender, stream });
+        Ok(client)
+    }
+
+    /// Numbers `frame` and queues it for every client, dropping the ones that fall behind.
+    fn broadcast(&mut self, mut frame: Value) {
+        self.next_seq += 1;
+        frame["seq"] = self.next_seq.into();
+
+        let frame = Frame::new(&frame);
+        self.clients.retain(|client, Client { sender, stream }| {
+            match sender.try_send(frame.line.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    eprintln!("Dropping client {client}, it is not keeping up");
+                    let _ = stream.shutdown(Shutdown::Both);
+                    false
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+
+        self.history.push(frame);
+        if self.history.len() > MAX_HISTORY {
+            self.compact();
+        }
+    }
+
+    /// Drops frames that a joining instance would overwrite anyway: edits whose fields were all
+    /// written again later, and presence that is outdated or of clients that left.
+    fn compact(&mut self) {
+        let mut written = HashSet::new();
+        let mut present = HashSet::new();
+        let mut history: Vec<Frame> = std::mem::take(&mut self.history)
+            .into_iter()
+            .rev()
+            .filter(|frame| match &frame.kind {
+                FrameKind::Presence { client } => {
+                    self.clients.contains_key(client) && present.insert(*client)
+                }
+                FrameKind::Edit { fields } => fields
+                    .iter()
+                    .fold(false, |new, field| written.insert(field.clone()) || new),
+                FrameKind::Other => false,
+            })
+            .collect();
+        history.reverse();
+        self.history = history;
+    }
+}
+
+/// Writes `lines` until the client disconnects or the relay drops it.
+fn write_lines(mut stream: TcpStream, lines: impl Iterator<Item = Arc<str>>) {
+    for line in lines {
+        if writeln!(stream, "{line}").is_err() {
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Could not listen on {address}: {error}");
+            std::process::exit(1);
+        }
+    };
+    println!("Relay listening on {address}");
+
+    let relay = Arc::new(Mutex::new(Relay::default()));
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let relay = relay.clone();
+                thread::spawn(move || serve(relay, stream));
+            }
+            Err(error) => eprintln!("Could not accept client: {error}"),
+        }
+    }
+}
+
+fn serve(relay: Arc<Mutex<Relay>>, stream: TcpStream) {
+    let joined = stream
+        .try_clone()
+        .and_then(|stream| relay.lock().unwrap().join(stream));
+    let client = match joined {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("Could not greet client: {error}");
+            return;
+        }
+    };
+    println!("Client {client} joined");
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        match serde_json::from_str::<Value>(&line) {
+            Ok(message) => relay.lock().unwrap().broadcast(json!({
+                "type": "message",
+                "client": client,
+                "message": message,
+            })),
+            Err(error) => eprintln!("Client {client} sent invalid JSON: {error}"),
+        }
+    }
+
+    let mut relay = relay.lock().unwrap();
+    relay.clients.remove(&client);
+    relay.broadcast(json!({ "type": "left", "client": client }));
+    println!("Client {client} left");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(client: u64, fields: &[&str]) -> Value {
+        let changes: Vec<Value> = fields.iter().map(|field| json!([field, null])).collect();
+        json!({
+            "type": "message",
+            "client": client,
+            "message": { "type": "edit", "changes": changes },
+        })
+    }
+
+    fn presence(client: u64) -> Value {
+        json!({ "type": "message", "client": client, "message": { "type": "presence" } })
+    }
+
+    #[test]
+    fn compaction_keeps_what_a_joining_instance_needs() {
+        let mut relay = Relay::default();
+        relay.broadcast(edit(0, &["Name", "Age"]));
+        relay.broadcast(presence(0));
+        relay.broadcast(edit(0, &["Name"]));
+        relay.broadcast(edit(1, &["Counter"]));
+        relay.broadcast(edit(1, &["Counter"]));
+        relay.broadcast(json!({ "type": "left", "client": 1 }));
+        relay.compact();
+
+        // No client is connected, so no presence survives.
+        let seqs: Vec<u64> = relay
+            .history
+            .iter()
+            .map(|frame| {
+                serde_json::from_str::<Value>(&frame.line).unwrap()["seq"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(seqs, [1, 3, 5]);
+    }
+}
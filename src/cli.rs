@@ -0,0 +1,189 @@
+use std::{path::PathBuf, time::Duration};
+
+use bevy::{MinimalPlugins, app::ScheduleRunnerPlugin, log::LogPlugin, prelude::*};
+
+use crate::{
+    events::{
+        error::EventFailed,
+        events::{AppEventKind, EventRejected, EventsPlugin},
+        person_changed::PersonChangedPlugin,
+        sets::EventsSet,
+        transaction::{EditFailed, Transaction},
+    },
+    persistence::{self, UnknownFields},
+    person_resource::PersonResource,
+    scripting::{Script, ScriptRunner, ScriptStep, ScriptingPlugin},
+};
+
+pub const USAGE: &str = "\
+Usage: bevy-events-poc apply [--load <file>] [--event <Event>[=<value>]]... [--script <script>] [--save <file>]
+
+Applies events to a person without opening a window.
+  --load <file>      RON file to start from (defaults to an empty person)
+  --event <spec>     event to apply, e.g. `GrowOlder` or `ChangeLocation=Paris`; repeatable
+  --script <script>  script to apply after the events, e.g. \"ChangeName 'Alice'; GrowOlder x3\"
+  --save <file>      RON file to write the resulting person to";
+
+struct ApplyOptions {
+    load: Option<PathBuf>,
+    save: Option<PathBuf>,
+    script: Script,
+}
+
+impl ApplyOptions {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = ApplyOptions {
+            load: None,
+            save: None,
+            script: Script::default(),
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {arg}"))
+            };
+            match arg.as_str() {
+                "--load" => options.load = Some(value()?.into()),
+                "--save" => options.save = Some(value()?.into()),
+                "--event" => options.script.steps.push(parse_event(&value()?)?),
+                "--script" => {
+                    let script: Script = value()?.parse()?;
+                    options.script.steps.extend(script.steps);
+                }
+                _ => return Err(format!("Unknown argument {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Parses `Event` or `Event=value`, where the value is the dialog result.
+fn parse_event(spec: &str) -> Result<ScriptStep, String> {
+    let (name, input) = match spec.split_once('=') {
+        Some((name, input)) => (name, Some(input.to_string())),
+        None => (spec, None),
+    };
+    let kind: AppEventKind = name.trim().parse()?;
+
+    match (kind.info().needs_dialog, &input) {
+        (true, None) => Err(format!(
+            "{name} opens a dialog, pass its value as {name}=<value>"
+        )),
+        (false, Some(_)) => Err(format!("{name} has no dialog to fill")),
+        _ => Ok(ScriptStep::Event {
+            kind,
+            input,
+            repeat: 1,
+        }),
+    }
+}
+
+#[derive(Resource)]
+struct ApplyState {
+    save: Option<PathBuf>,
+    /// Carried over from the loaded file so saving keeps them.
+    unknown: UnknownFields,
+    failures: usize,
+}
+
+/// Runs `apply` headless: events are fed through the regular router and handlers, with dialog
+/// results injected directly, until the script drains.
+pub fn apply(args: impl Iterator<Item = String>) -> AppExit {
+    let options = match ApplyOptions::parse(args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return AppExit::from_code(2);
+        }
+    };
+
+    let (person, unknown) = match &options.load {
+        Some(path) => match persistence::load_person(path) {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                eprintln!("Could not load {}: {error}", path.display());
+                return AppExit::from_code(2);
+            }
+        },
+        None => (PersonResource::default(), UnknownFields::new()),
+    };
+
+    let mut runner = ScriptRunner::default();
+    runner.run(options.script);
+
+    App::new()
+        .add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)),
+            LogPlugin::default(),
+            EventsPlugin::default(),
+            PersonChangedPlugin,
+            ScriptingPlugin,
+        ))
+        .insert_resource(person)
+        .insert_resource(runner)
+        .insert_resource(ApplyState {
+            save: options.save,
+            unknown,
+            failures: 0,
+        })
+        .add_systems(
+            Update,
+            (count_failures, finish_when_idle)
+                .chain()
+                .in_set(EventsSet::Post),
+        )
+        .run()
+}
+
+fn count_failures(
+    mut state: ResMut<ApplyState>,
+    mut rejected_reader: EventReader<EventRejected>,
+    mut failed_reader: EventReader<EventFailed>,
+    mut edit_failed_reader: EventReader<EditFailed>,
+) {
+    for rejected in rejected_reader.read() {
+        eprintln!("{:?} failed: {}", rejected.event.kind(), rejected.reason);
+        state.failures += 1;
+    }
+    for failed in edit_failed_reader.read() {
+        eprintln!(
+            "Edit transaction {} failed: {}",
+            failed.transaction, failed.reason
+        );
+        state.failures += 1;
+    }
+    // Handler errors are logged where they are raised.
+    state.failures += failed_reader.read().count();
+}
+
+fn finish_when_idle(
+    mut state: ResMut<ApplyState>,
+    mut exit_writer: EventWriter<AppExit>,
+    runner: Res<ScriptRunner>,
+    person: Res<PersonResource>,
+    transaction: Res<Transaction>,
+) {
+    if !runner.is_idle() {
+        return;
+    }
+
+    if transaction.is_open() {
+        eprintln!("The script left an edit transaction open, its changes were not applied");
+        state.failures += 1;
+    }
+
+    if let Some(path) = state.save.clone()
+        && let Err(error) = persistence::save_person(&path, &person, &state.unknown)
+    {
+        eprintln!("Could not save {}: {error}", path.display());
+        state.failures += 1;
+    }
+
+    exit_writer.write(if state.failures == 0 {
+        AppExit::Success
+    } else {
+        AppExit::error()
+    });
+}
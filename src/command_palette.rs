@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts, EguiPrimaryContextPass,
+    egui::{self, Align2, Color32, Key, RichText},
+};
+
+use crate::{
+    events::{
+        events::{AppEventKind, EventAvailability},
+        person_changed::PersonView,
+        source::{AppEventWriter, EventSource},
+    },
+    localization::Localization,
+    shortcuts::Shortcuts,
+};
+
+/// State of the Ctrl+P command palette listing every registered `AppEvent`.
+#[derive(Resource, Default)]
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+}
+
+pub struct CommandPalettePlugin;
+
+impl Plugin for CommandPalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandPalette>()
+            .add_systems(Update, toggle_command_palette)
+            .add_systems(
+                EguiPrimaryContextPass,
+                command_palette_window.run_if(|palette: Res<CommandPalette>| palette.open),
+            );
+    }
+}
+
+fn toggle_command_palette(keys: Res<ButtonInput<KeyCode>>, mut palette: ResMut<CommandPalette>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keys.just_pressed(KeyCode::KeyP) {
+        palette.open = !palette.open;
+        palette.query.clear();
+        palette.selected = 0;
+    }
+}
+
+fn command_palette_window(
+    mut contexts: EguiContexts,
+    mut palette: ResMut<CommandPalette>,
+    mut event_writer: AppEventWriter,
+    localization: Res<Localization>,
+    person: PersonView,
+    availability: Res<EventAvailability>,
+    shortcuts: Res<Shortcuts>,
+) {
+    let ctx = contexts.ctx_mut().unwrap();
+
+    let mut matches: Vec<(i32, AppEventKind)> = AppEventKind::ALL
+        .iter()
+        .filter_map(|kind| {
+            let name = localization.text(kind.info().name_id);
+            fuzzy_score(&palette.query, &name).map(|score| (score, *kind))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+    palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+
+    let (down, up, enter, escape) = ctx.input(|input| {
+        (
+            input.key_pressed(Key::ArrowDown),
+            input.key_pressed(Key::ArrowUp),
+            input.key_pressed(Key::Enter),
+            input.key_pressed(Key::Escape),
+        )
+    });
+    if down {
+        palette.selected = (palette.selected + 1).min(matches.len().saturating_sub(1));
+    }
+    if up {
+        palette.selected = palette.selected.saturating_sub(1);
+    }
+
+    let mut dispatched = None;
+    egui::Window::new(localization.text("palette-title"))
+        .id(egui::Id::new("Command Palette"))
+        .collapsible(false)
+        .resizable(false)
+        .title_bar(false)
+        .order(egui::Order::Foreground)
+        .anchor(Align2::CENTER_TOP, egui::Vec2::new(0.0, 48.0))
+        .show(ctx, |ui| {
+            ui.set_width(400.0);
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut palette.query)
+                    .hint_text(localization.text("palette-placeholder"))
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if response.changed() {
+                palette.selected = 0;
+            }
+            ui.add_space(8.0);
+
+            if matches.is_empty() {
+                ui.label(RichText::new(localization.text("palette-empty")).weak());
+            }
+
+            for (index, (_, kind)) in matches.iter().enumerate() {
+                let info = kind.info();
+                let event = kind.construct(&person);
+                let check = availability.check(&event);
+
+                let mut name = localization.text(info.name_id);
+                if info.needs_dialog {
+                    name.push('…');
+                }
+
+                ui.horizontal(|ui| {
+                    let label = ui.add_enabled(
+                        check.is_ok(),
+                        egui::Button::selectable(index == palette.selected, name),
+                    );
+                    let label = match &check {
+                        Ok(()) => label.on_hover_text(localization.text(info.description_id)),
+                        Err(reason) => label.on_disabled_hover_text(localization.format(reason)),
+                    };
+
+                    if let Some(chord) = shortcuts.chord_for(*kind) {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(
+                                RichText::new(chord.to_string())
+                                    .color(Color32::GRAY)
+                                    .monospace(),
+                            );
+                        });
+                    }
+
+                    let activated = label.clicked() || (enter && index == palette.selected);
+                    if activated && check.is_ok() {
+                        dispatched = Some(event);
+                    }
+                });
+            }
+        });
+
+    if let Some(event) = dispatched {
+        event_writer.write(event, EventSource::Palette);
+        palette.open = false;
+    }
+    if escape {
+        palette.open = false;
+    }
+}
+
+/// Case-insensitive subsequence match; consecutive and word-start hits score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut position = 0;
+    let mut previous = None;
+    let mut score = 0;
+
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let index = (position..candidate.len())
+            .find(|&i| candidate[i].to_lowercase().eq(query_char.to_lowercase()))?;
+
+        score += 1;
+        if index > 0 && previous == Some(index - 1) {
+            score += 5;
+        }
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
@@ -0,0 +1,253 @@
+use bevy::{
+    color::{Oklaba, Srgba, palettes::css},
+    prelude::*,
+};
+
+use crate::{
+    events::{person_changed::PersonView, sets::EventsSet, transaction::Transaction},
+    localization::LocalizedText,
+    person_resource::{PersonField, PersonResource, PersonValue},
+};
+
+/// Named colors the color name is resolved against, by localization message id.
+const COLOR_PALETTE: [(&str, Srgba); 12] = [
+    ("color-black", css::BLACK),
+    ("color-gray", css::GRAY),
+    ("color-white", css::WHITE),
+    ("color-red", css::RED),
+    ("color-orange", css::ORANGE),
+    ("color-yellow", css::YELLOW),
+    ("color-green", css::GREEN),
+    ("color-cyan", css::AQUA),
+    ("color-blue", css::BLUE),
+    ("color-purple", css::PURPLE),
+    ("color-pink", css::PINK),
+    ("color-brown", css::BROWN),
+];
+
+/// A read-only attribute computed from other fields of `PersonResource`.
+pub struct DerivedField {
+    /// Localization message id of the label.
+    pub label_id: &'static str,
+    /// The fields `compute` reads. It only runs again when one of them changed.
+    pub inputs: &'static [PersonField],
+    pub compute: fn(&PersonResource) -> LocalizedText,
+}
+
+struct DerivedEntry {
+    field: DerivedField,
+    inputs: Vec<PersonValue>,
+    value: Option<LocalizedText>,
+}
+
+/// Registered derived fields with their last computed values.
+#[derive(Resource, Default)]
+pub struct DerivedFields {
+    entries: Vec<DerivedEntry>,
+}
+
+impl DerivedFields {
+    pub fn register(&mut self, field: DerivedField) {
+        self.entries.push(DerivedEntry {
+            field,
+            inputs: Vec::new(),
+            value: None,
+        });
+    }
+
+    /// Labels and values of every field computed so far, in registration order.
+    pub fn values(&self) -> impl Iterator<Item = (&'static str, &LocalizedText)> {
+        self.entries.iter().filter_map(|entry| {
+            entry
+                .value
+                .as_ref()
+                .map(|value| (entry.field.label_id, value))
+        })
+    }
+
+    /// Recomputes the fields whose inputs differ from the last computation.
+    fn update(&mut self, person: &PersonResource) {
+        for entry in &mut self.entries {
+            let inputs: Vec<PersonValue> = entry
+                .field
+                .inputs
+                .iter()
+                .map(|field| person.get(*field))
+                .collect();
+            if entry.value.is_some() && inputs == entry.inputs {
+                continue;
+            }
+
+            entry.value = Some((entry.field.compute)(person));
+            entry.inputs = inputs;
+        }
+    }
+}
+
+pub fn age_group(person: &PersonResource) -> LocalizedText {
+    LocalizedText::new(match person.age {
+        0..13 => "age-group-child",
+        13..18 => "age-group-teen",
+        18..65 => "age-group-adult",
+        _ => "age-group-senior",
+    })
+}
+
+/// The `COLOR_PALETTE` entry closest to the color in Oklab.
+pub fn color_name(person: &PersonResource) -> LocalizedText {
+    let color = Oklaba::from(person.color);
+    let distance = |entry: &Srgba| {
+        let entry = Oklaba::from(*entry);
+        (color.lightness - entry.lightness).powi(2)
+            + (color.a - entry.a).powi(2)
+            + (color.b - entry.b).powi(2)
+    };
+
+    let (id, _) = COLOR_PALETTE
+        .iter()
+        .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+        .unwrap();
+    LocalizedText::new(*id)
+}
+
+pub fn counter_parity(person: &PersonResource) -> LocalizedText {
+    LocalizedText::new(if person.counter % 2 == 0 {
+        "parity-even"
+    } else {
+        "parity-odd"
+    })
+}
+
+/// Keeps `DerivedFields` up to date with the person, including staged transaction changes.
+///
+/// Registers the age group, color name and counter parity.
+pub struct DerivedFieldsPlugin;
+
+impl Plugin for DerivedFieldsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut fields = DerivedFields::default();
+        fields.register(DerivedField {
+            label_id: "derived-age-group",
+            inputs: &[PersonField::Age],
+            compute: age_group,
+        });
+        fields.register(DerivedField {
+            label_id: "derived-color-name",
+            inputs: &[PersonField::Color],
+            compute: color_name,
+        });
+        fields.register(DerivedField {
+            label_id: "derived-counter-parity",
+            inputs: &[PersonField::Counter],
+            compute: counter_parity,
+        });
+
+        app.insert_resource(fields).add_systems(
+            Update,
+            update_derived_fields
+                .run_if(
+                    resource_changed::<PersonResource>
+                        .or(resource_changed::<Transaction>)
+                        .or(resource_changed::<DerivedFields>),
+                )
+                .in_set(EventsSet::Post),
+        );
+    }
+}
+
+fn update_derived_fields(mut fields: ResMut<DerivedFields>, person: PersonView) {
+    // Only registering a field counts as a change, or the run condition would hold every frame.
+    fields.bypass_change_detection().update(&person);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn person(age: u32, color: Color) -> PersonResource {
+        PersonResource {
+            age,
+            color,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn age_groups() {
+        for (age, id) in [
+            (0, "age-group-child"),
+            (12, "age-group-child"),
+            (13, "age-group-teen"),
+            (17, "age-group-teen"),
+            (18, "age-group-adult"),
+            (64, "age-group-adult"),
+            (65, "age-group-senior"),
+        ] {
+            assert_eq!(
+                age_group(&person(age, Color::BLACK)).to_string(),
+                id,
+                "{age}"
+            );
+        }
+    }
+
+    #[test]
+    fn color_names_pick_the_nearest_palette_entry() {
+        for (color, id) in [
+            (Color::srgb(1.0, 0.0, 0.0), "color-red"),
+            (Color::srgb(0.9, 0.1, 0.05), "color-red"),
+            (Color::srgb(0.05, 0.05, 0.05), "color-black"),
+            (Color::srgb(0.97, 0.97, 0.95), "color-white"),
+            (Color::srgb(0.1, 0.2, 0.95), "color-blue"),
+            (Color::srgb(1.0, 0.85, 0.1), "color-yellow"),
+        ] {
+            assert_eq!(color_name(&person(0, color)).to_string(), id, "{color:?}");
+        }
+    }
+
+    static AGE_GROUP_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn counted_age_group(person: &PersonResource) -> LocalizedText {
+        AGE_GROUP_CALLS.fetch_add(1, Ordering::Relaxed);
+        age_group(person)
+    }
+
+    #[test]
+    fn update_only_recomputes_fields_whose_inputs_changed() {
+        let mut fields = DerivedFields::default();
+        fields.register(DerivedField {
+            label_id: "derived-age-group",
+            inputs: &[PersonField::Age],
+            compute: counted_age_group,
+        });
+        fields.register(DerivedField {
+            label_id: "derived-counter-parity",
+            inputs: &[PersonField::Counter],
+            compute: counter_parity,
+        });
+        let values = |fields: &DerivedFields| -> Vec<String> {
+            fields
+                .values()
+                .map(|(_, value)| value.to_string())
+                .collect()
+        };
+
+        let mut person = person(30, Color::BLACK);
+        fields.update(&person);
+        assert_eq!(AGE_GROUP_CALLS.load(Ordering::Relaxed), 1);
+        assert_eq!(values(&fields), ["age-group-adult", "parity-even"]);
+
+        person.counter = 3;
+        person.name = "Ada".to_string();
+        fields.update(&person);
+        assert_eq!(AGE_GROUP_CALLS.load(Ordering::Relaxed), 1);
+        assert_eq!(values(&fields), ["age-group-adult", "parity-odd"]);
+
+        person.age = 70;
+        fields.update(&person);
+        assert_eq!(AGE_GROUP_CALLS.load(Ordering::Relaxed), 2);
+        assert_eq!(values(&fields), ["age-group-senior", "parity-odd"]);
+    }
+}
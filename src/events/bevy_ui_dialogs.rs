@@ -0,0 +1,397 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    core_widgets::{Activate, Callback, CoreButton, CoreButtonPlugin, CoreWidgetsPlugins},
+    ecs::system::SystemId,
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    input_focus::{
+        AutoFocus, InputDispatchPlugin, InputFocus,
+        tab_navigation::{TabGroup, TabIndex, TabNavigationPlugin},
+    },
+    prelude::*,
+};
+use bevy_egui::input::EguiWantsInput;
+
+use crate::{
+    events::{
+        dialog_backend::{DialogAction, DialogResponse, DialogWantsInput, DialogWindow},
+        dialog_definition::{DialogDefinition, DialogDefinitions},
+        presence::EditorPresence,
+        sets::EventsSet,
+    },
+    localization::Localization,
+};
+
+const BACKGROUND: Color = Color::srgb(0.15, 0.15, 0.17);
+const INPUT_BACKGROUND: Color = Color::srgb(0.08, 0.08, 0.09);
+const INPUT_FOCUSED: Color = Color::srgb(0.11, 0.11, 0.14);
+const POSITIVE: Color = Color::srgb(0.27, 0.55, 0.27);
+const NEGATIVE: Color = Color::srgb(0.78, 0.2, 0.27);
+const TEXT: Color = Color::WHITE;
+const HINT: Color = Color::srgb(0.5, 0.5, 0.5);
+const WARNING: Color = Color::srgb(0.9, 0.7, 0.31);
+const ERROR: Color = Color::srgb(0.9, 0.31, 0.31);
+
+/// A text node of a dialog, refreshed from the dialog and its definition every frame.
+#[derive(Component)]
+struct DialogText {
+    dialog: Entity,
+    part: DialogPart,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum DialogPart {
+    Title,
+    /// Other editors and the stale warning.
+    Notice,
+    Input,
+    /// The first failing validator.
+    Error,
+    Positive,
+    Negative,
+}
+
+/// The text input of a dialog, which receives typing while it has `InputFocus`.
+#[derive(Component)]
+struct DialogInput {
+    dialog: Entity,
+}
+
+#[derive(Component)]
+struct DialogButton {
+    dialog: Entity,
+    action: DialogAction,
+}
+
+/// The one-shot system that `CoreButton`s of `D` dialogs run when activated.
+#[derive(Resource)]
+struct DialogButtonCallback<D> {
+    system: SystemId<In<Activate>>,
+    marker: PhantomData<fn() -> D>,
+}
+
+/// Adds the widget, focus dispatch and tab navigation plugins the dialogs rely on, unless the app
+/// already has them. `CoreWidgetsPlugins` is a group, it is checked through its button plugin.
+pub(crate) fn add_widget_plugins(app: &mut App) {
+    if !app.is_plugin_added::<CoreButtonPlugin>() {
+        app.add_plugins(CoreWidgetsPlugins);
+    }
+    if !app.is_plugin_added::<InputDispatchPlugin>() {
+        app.add_plugins(InputDispatchPlugin);
+    }
+    if !app.is_plugin_added::<TabNavigationPlugin>() {
+        app.add_plugins(TabNavigationPlugin);
+    }
+}
+
+/// Runs before `EventsSet::Handle`, so a submitted result is handled in the same frame.
+pub(crate) fn add_systems<D: DialogWindow>(app: &mut App) {
+    let system = app.world_mut().register_system(activate_dialog_button::<D>);
+    app.insert_resource(DialogButtonCallback::<D> {
+        system,
+        marker: PhantomData,
+    })
+    .add_systems(
+        Update,
+        (
+            build_dialog_nodes::<D>,
+            type_into_dialog::<D>,
+            update_dialog_text::<D>,
+        )
+            .chain()
+            .run_if(any_with_component::<D>)
+            .after(EventsSet::OpenDialogs)
+            .before(EventsSet::Handle),
+    );
+
+    if !app.world().contains_resource::<DialogFocusSystems>() {
+        app.init_resource::<DialogFocusSystems>()
+            .add_systems(PreUpdate, focus_dialog_inputs);
+    }
+}
+
+/// Marks that the systems shared by every dialog type were added.
+#[derive(Resource, Default)]
+struct DialogFocusSystems;
+
+/// Turns newly opened dialog entities into a centered panel of `bevy_ui` nodes.
+///
+/// The input takes focus when spawned, and Tab moves on to the buttons.
+fn build_dialog_nodes<D: DialogWindow>(
+    mut commands: Commands,
+    opened: Query<Entity, Added<D>>,
+    callback: Res<DialogButtonCallback<D>>,
+) {
+    for dialog in &opened {
+        let text = |part: DialogPart, size: f32, color: Color| {
+            (
+                Text::default(),
+                TextFont::from_font_size(size),
+                TextColor(color),
+                DialogText { dialog, part },
+            )
+        };
+        let button = |action: DialogAction, part: DialogPart, color: Color, index: i32| {
+            (
+                CoreButton {
+                    on_activate: Callback::System(callback.system),
+                },
+                TabIndex(index),
+                Node {
+                    min_width: Val::Px(120.0),
+                    height: Val::Px(36.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(color),
+                BorderRadius::all(Val::Px(10.0)),
+                DialogButton { dialog, action },
+                children![text(part, 16.0, TEXT)],
+            )
+        };
+
+        let input = (
+            Interaction::default(),
+            TabIndex(0),
+            AutoFocus,
+            Node {
+                min_width: Val::Px(240.0),
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(INPUT_BACKGROUND),
+            BorderRadius::all(Val::Px(4.0)),
+            DialogInput { dialog },
+            children![text(DialogPart::Input, 16.0, TEXT)],
+        );
+
+        commands.entity(dialog).insert((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            GlobalZIndex(10),
+            children![(
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(12.0),
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                BackgroundColor(BACKGROUND),
+                BorderRadius::all(Val::Px(8.0)),
+                TabGroup::modal(),
+                children![
+                    text(DialogPart::Title, 22.0, TEXT),
+                    text(DialogPart::Notice, 14.0, WARNING),
+                    input,
+                    text(DialogPart::Error, 14.0, ERROR),
+                    (
+                        Node {
+                            column_gap: Val::Px(12.0),
+                            ..default()
+                        },
+                        children![
+                            button(DialogAction::Submit, DialogPart::Positive, POSITIVE, 1),
+                            button(DialogAction::Cancel, DialogPart::Negative, NEGATIVE, 2),
+                        ],
+                    ),
+                ],
+            )],
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn type_into_dialog<D: DialogWindow>(
+    mut commands: Commands,
+    mut key_reader: EventReader<KeyboardInput>,
+    mut dialogs: Query<&mut D>,
+    inputs: Query<&DialogInput>,
+    focus: Res<InputFocus>,
+    keys: Res<ButtonInput<KeyCode>>,
+    egui_wants_input: Option<Res<EguiWantsInput>>,
+    definitions: Res<DialogDefinitions>,
+    assets: Res<Assets<DialogDefinition>>,
+) {
+    // Keys typed into an egui text field belong to egui.
+    if egui_wants_input.is_some_and(|wants_input| wants_input.wants_any_keyboard_input()) {
+        key_reader.clear();
+        return;
+    }
+    let Some(DialogInput { dialog }) = focus.0.and_then(|entity| inputs.get(entity).ok()) else {
+        key_reader.clear();
+        return;
+    };
+    let Ok(mut window) = dialogs.get_mut(*dialog) else {
+        key_reader.clear();
+        return;
+    };
+
+    let definition = definitions.get(D::KIND, &assets);
+    // Characters typed with a modifier are shortcuts, e.g. Ctrl+A, and not part of the input.
+    let modified = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::AltLeft,
+        KeyCode::AltRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    for key in key_reader.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+
+        let action = match &key.logical_key {
+            Key::Enter if definition.accepts(window.input()) => Some(DialogAction::Submit),
+            Key::Escape => Some(DialogAction::Cancel),
+            Key::Backspace => {
+                window.input_mut().pop();
+                None
+            }
+            Key::Character(_) if modified => None,
+            Key::Character(characters) => {
+                window.input_mut().push_str(&definition.filter(characters));
+                None
+            }
+            Key::Space => {
+                window.input_mut().push_str(&definition.filter(" "));
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(action) = action {
+            commands.trigger(DialogResponse {
+                dialog: *dialog,
+                action,
+            });
+            break;
+        }
+    }
+}
+
+/// Run by a `CoreButton` of a `D` dialog when it is clicked, or pressed with Enter or Space
+/// while focused.
+fn activate_dialog_button<D: DialogWindow>(
+    In(Activate(entity)): In<Activate>,
+    mut commands: Commands,
+    buttons: Query<&DialogButton>,
+    dialogs: Query<&D>,
+    definitions: Res<DialogDefinitions>,
+    assets: Res<Assets<DialogDefinition>>,
+) {
+    let Ok(button) = buttons.get(entity) else {
+        return;
+    };
+    let Ok(window) = dialogs.get(button.dialog) else {
+        return;
+    };
+    if button.action == DialogAction::Submit
+        && !definitions.get(D::KIND, &assets).accepts(window.input())
+    {
+        return;
+    }
+
+    commands.trigger(DialogResponse {
+        dialog: button.dialog,
+        action: button.action,
+    });
+}
+
+/// Formats every text of `D` dialogs, so locale switches and reloaded definitions apply to open
+/// dialogs.
+#[allow(clippy::too_many_arguments)]
+fn update_dialog_text<D: DialogWindow>(
+    mut texts: Query<(&DialogText, &mut Text, &mut TextColor)>,
+    mut inputs: Query<(Entity, &DialogInput, &mut BackgroundColor)>,
+    dialogs: Query<&D>,
+    focus: Res<InputFocus>,
+    localization: Res<Localization>,
+    presence: Res<EditorPresence>,
+    definitions: Res<DialogDefinitions>,
+    assets: Res<Assets<DialogDefinition>>,
+) {
+    let definition = definitions.get(D::KIND, &assets);
+
+    for (entity, input, mut background) in &mut inputs {
+        if dialogs.contains(input.dialog) {
+            let focused = focus.0 == Some(entity);
+            background.0 = if focused {
+                INPUT_FOCUSED
+            } else {
+                INPUT_BACKGROUND
+            };
+        }
+    }
+
+    for (text, mut content, mut color) in &mut texts {
+        let Ok(window) = dialogs.get(text.dialog) else {
+            continue;
+        };
+
+        let value = match text.part {
+            DialogPart::Title => localization.format(&definition.title),
+            DialogPart::Notice => {
+                let mut notices = Vec::new();
+                if let Some(editors) = presence.header(D::KIND) {
+                    notices.push(localization.format(&editors));
+                }
+                if window.stale() {
+                    notices.push(localization.text("dialog-stale"));
+                }
+                notices.join("\n")
+            }
+            DialogPart::Input if window.input().is_empty() => {
+                color.0 = HINT;
+                definition
+                    .hint
+                    .as_ref()
+                    .map(|hint| localization.format(hint))
+                    .unwrap_or_default()
+            }
+            DialogPart::Input => {
+                color.0 = TEXT;
+                window.input().to_string()
+            }
+            DialogPart::Error => match definition.validate(window.input()) {
+                Err(error) if !window.input().is_empty() => localization.format(&error),
+                _ => String::new(),
+            },
+            DialogPart::Positive => localization.format(&definition.positive_action),
+            DialogPart::Negative => localization.format(&definition.negative_action),
+        };
+
+        if content.0 != value {
+            content.0 = value;
+        }
+    }
+}
+
+/// Focuses the input of the dialog clicked last, and tracks whether a dialog has focus.
+fn focus_dialog_inputs(
+    mut focus: ResMut<InputFocus>,
+    mut wants_input: ResMut<DialogWantsInput>,
+    clicked: Query<(Entity, &Interaction), (Changed<Interaction>, With<DialogInput>)>,
+    parts: Query<(), Or<(With<DialogInput>, With<DialogButton>)>>,
+) {
+    for (entity, interaction) in &clicked {
+        if *interaction == Interaction::Pressed {
+            focus.set(entity);
+        }
+    }
+
+    let focused = focus.0.is_some_and(|entity| parts.contains(entity));
+    if wants_input.0 != focused {
+        wants_input.0 = focused;
+    }
+}
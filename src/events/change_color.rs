@@ -1,4 +1,19 @@
-use crate::{create_event, person_resource::PersonResource};
+use crate::{
+    create_event,
+    events::{
+        error::{HandlerError, report_failure},
+        events::AppEvent,
+        mode::EventsMode,
+        person_changed::PersonEditor,
+        prefill::{DialogSubmitted, PrefilledInputs},
+        presence::{DialogOpen, EditorPresence},
+        registry::RoutedEvent,
+        stale::StalePolicy,
+    },
+    localization::Localization,
+    person_resource::PersonResource,
+};
+use bevy::ecs::event::EventId;
 use bevy_egui::{
     EguiContexts,
     egui::{self, Color32, Frame, Margin, Pos2, RichText, Stroke},
@@ -12,38 +27,93 @@ create_event!(
 );
 
 #[derive(BufferedEvent, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpenColorPickerEvent {
     pub color: Color,
+    /// `PersonResource::revision` that `color` was read at.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub revision: Option<u64>,
+}
+
+impl From<&PersonResource> for OpenColorPickerEvent {
+    fn from(person: &PersonResource) -> Self {
+        OpenColorPickerEvent {
+            color: person.color.clone(),
+            revision: Some(person.revision),
+        }
+    }
 }
 
 #[derive(Component)]
 struct ColorPicker {
-    title: String,
+    event: OpenColorPickerEvent,
     color: Color,
+    /// The person changed while the picker was open and the event was refreshed.
+    stale: bool,
+    /// The id on `EventRouted` of the event that opened the picker.
+    routed: Option<EventId<AppEvent>>,
+}
+
+enum PickerAction {
+    Save,
+    Cancel,
 }
 
 fn open_color_picker(
+    In(event): In<OpenColorPickerEvent>,
     mut commands: Commands,
-    mut reader: EventReader<OpenColorPickerEvent>,
+    mut prefilled: ResMut<PrefilledInputs>,
+    mut person: PersonEditor,
+    mut submitted_writer: EventWriter<DialogSubmitted>,
     pickers: Query<(Entity, &ColorPicker)>,
-) {
-    for event in reader.read() {
-        for (entity, _) in pickers {
-            commands.entity(entity).despawn();
+    stale_policy: Res<StalePolicy>,
+) -> Result<(), HandlerError> {
+    if let Some(input) = prefilled.take(OpenColorPickerEvent::KIND) {
+        match Srgba::hex(&input) {
+            Ok(color) => {
+                // Colors are absolute, so a rebased color is saved as is.
+                stale_policy.check(event.revision, person.revision)?;
+                let routed = person.routed();
+                save_color(
+                    &mut person,
+                    &mut submitted_writer,
+                    &event,
+                    color.into(),
+                    routed,
+                );
+                return Ok(());
+            }
+            Err(error) => warn!("Invalid prefilled color `{input}`: {error}"),
         }
+    }
 
-        commands.spawn(ColorPicker {
-            title: "Change Color".to_string(),
-            color: event.color.clone(),
-        });
+    for (entity, _) in pickers {
+        commands.entity(entity).despawn();
     }
+
+    commands.spawn((
+        ColorPicker {
+            color: event.color.clone(),
+            event,
+            stale: false,
+            routed: person.routed(),
+        },
+        DialogOpen(OpenColorPickerEvent::KIND),
+    ));
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn color_picker_window(
     mut contexts: EguiContexts,
     mut commands: Commands,
     mut color_picker: Query<(Entity, &mut ColorPicker)>,
-    mut person: ResMut<PersonResource>,
+    mut person: PersonEditor,
+    mut submitted_writer: EventWriter<DialogSubmitted>,
+    mode: Res<EventsMode>,
+    localization: Res<Localization>,
+    presence: Res<EditorPresence>,
+    stale_policy: Res<StalePolicy>,
     window: Single<&Window>,
 ) {
     if color_picker.is_empty() {
@@ -52,7 +122,13 @@ fn color_picker_window(
 
     let (entity, mut color_picker) = color_picker.single_mut().unwrap();
 
+    if !mode.allows(OpenColorPickerEvent::ACCESS) {
+        commands.entity(entity).despawn();
+        return;
+    }
+
     let mut is_open = true;
+    let mut action = None;
     egui::Window::new("")
         .open(&mut is_open)
         .interactable(true)
@@ -65,9 +141,50 @@ fn color_picker_window(
             window.height() / 2.0 - 500.0 / 2.0,
         ))
         .show(contexts.ctx_mut().unwrap(), |ui| {
-            render_color_picker_content(ui, &mut color_picker, &mut person, &mut commands, entity);
+            if let Some(editors) = presence.header(OpenColorPickerEvent::KIND) {
+                ui.colored_label(
+                    Color32::from_rgb(230, 180, 80),
+                    localization.format(&editors),
+                );
+            }
+            if color_picker.stale {
+                ui.colored_label(
+                    Color32::from_rgb(230, 180, 80),
+                    localization.text("dialog-stale"),
+                );
+            }
+            action = render_color_picker_content(ui, &mut color_picker, &localization);
         });
 
+    match action {
+        Some(PickerAction::Save) => {
+            match stale_policy.check(color_picker.event.revision, person.revision) {
+                // Colors are absolute, so a rebased color is saved as is.
+                Ok(_) => {
+                    let color = color_picker.color;
+                    save_color(
+                        &mut person,
+                        &mut submitted_writer,
+                        &color_picker.event,
+                        color,
+                        color_picker.routed,
+                    );
+                    commands.entity(entity).despawn();
+                }
+                Err(stale) if stale.policy == StalePolicy::Prompt => {
+                    color_picker.event = OpenColorPickerEvent::from(&*person);
+                    color_picker.stale = true;
+                }
+                Err(stale) => {
+                    report_failure(&mut commands, color_picker.event.clone().into(), stale);
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+        Some(PickerAction::Cancel) => commands.entity(entity).despawn(),
+        None => {}
+    }
+
     if !is_open {
         commands.entity(entity).despawn();
     }
@@ -76,16 +193,15 @@ fn color_picker_window(
 fn render_color_picker_content(
     ui: &mut egui::Ui,
     color_picker: &mut ColorPicker,
-    person: &mut PersonResource,
-    commands: &mut Commands,
-    entity: Entity,
-) {
+    localization: &Localization,
+) -> Option<PickerAction> {
+    let mut action = None;
     ui.set_min_width(450.0);
     ui.set_max_width(450.0);
 
     Frame::new().show(ui, |ui| {
         ui.label(
-            egui::RichText::new(color_picker.title.clone())
+            egui::RichText::new(localization.text("color-picker-title"))
                 .size(18.0)
                 .strong()
                 .color(Color32::WHITE),
@@ -93,7 +209,7 @@ fn render_color_picker_content(
 
         ui.add_space(12.0);
 
-        render_enhanced_color_bands(ui, color_picker);
+        render_enhanced_color_bands(ui, color_picker, localization);
 
         ui.add_space(24.0);
 
@@ -107,7 +223,8 @@ fn render_color_picker_content(
                         if ui
                             .add(
                                 egui::Button::new(
-                                    RichText::new("Save").color(Color32::from_rgb(255, 255, 255)),
+                                    RichText::new(localization.text("button-save"))
+                                        .color(Color32::from_rgb(255, 255, 255)),
                                 )
                                 .fill(Color32::from_rgb(70, 140, 70))
                                 .stroke(Stroke::new(1.0, Color32::from_rgb(90, 160, 90)))
@@ -116,8 +233,7 @@ fn render_color_picker_content(
                             )
                             .clicked()
                         {
-                            person.color = color_picker.color.clone();
-                            commands.entity(entity).despawn();
+                            action = Some(PickerAction::Save);
                         }
 
                         ui.add_space(12.0);
@@ -125,7 +241,8 @@ fn render_color_picker_content(
                         if ui
                             .add(
                                 egui::Button::new(
-                                    RichText::new("Cancel").color(Color32::from_rgb(255, 255, 255)),
+                                    RichText::new(localization.text("button-cancel"))
+                                        .color(Color32::from_rgb(255, 255, 255)),
                                 )
                                 .fill(Color32::from_rgb(200, 50, 70))
                                 .stroke(Stroke::new(1.0, Color32::from_rgb(90, 90, 90)))
@@ -134,18 +251,44 @@ fn render_color_picker_content(
                             )
                             .clicked()
                         {
-                            commands.entity(entity).despawn();
+                            action = Some(PickerAction::Cancel);
                         }
                     });
                 });
             });
     });
+    action
+}
+
+fn save_color(
+    person: &mut PersonEditor,
+    submitted_writer: &mut EventWriter<DialogSubmitted>,
+    event: &OpenColorPickerEvent,
+    color: Color,
+    routed: Option<EventId<AppEvent>>,
+) {
+    person.edit_routed(routed, event.clone(), |person| {
+        person.color = color;
+    });
+    submitted_writer.write(DialogSubmitted {
+        kind: OpenColorPickerEvent::KIND,
+        input: color.to_srgba().to_hex(),
+    });
 }
 
-fn render_enhanced_color_bands(ui: &mut egui::Ui, color_picker: &mut ColorPicker) {
+fn render_enhanced_color_bands(
+    ui: &mut egui::Ui,
+    color_picker: &mut ColorPicker,
+    localization: &Localization,
+) {
     let mut color_arrays = convert_color_f32(&color_picker.color);
 
-    render_color_bands(ui, "What is your color?", &mut color_arrays, "Who you is~");
+    render_color_bands(
+        ui,
+        &localization.text("color-picker-label"),
+        &mut color_arrays,
+        &localization.text("color-picker-description"),
+    );
 
     color_picker.color = convert_arrays_to_colors(&color_arrays);
 }
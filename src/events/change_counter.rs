@@ -1,33 +1,112 @@
+use std::{fmt, num::ParseIntError};
+
 use bevy::ecs::event::BufferedEvent;
 use rand::Rng;
 
-use crate::{create_event_with_dialog, person_resource::PersonResource};
+use crate::{
+    create_event_with_dialog,
+    events::{
+        limits::{Limits, OutOfBounds},
+        person_changed::PersonEditor,
+        stale::{StaleEvent, StalePolicy, Staleness},
+    },
+    localization::LocalizedText,
+    notify::Notify,
+    person_resource::PersonResource,
+};
 
 #[derive(BufferedEvent, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeCounterEvent {
     pub counter: u32,
+    /// `PersonResource::revision` that `counter` was read at.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub revision: Option<u64>,
+}
+
+impl From<&PersonResource> for ChangeCounterEvent {
+    fn from(person: &PersonResource) -> Self {
+        ChangeCounterEvent {
+            counter: person.counter,
+            revision: Some(person.revision),
+        }
+    }
 }
 
 create_event_with_dialog!(
     ChangeCounter,
     ChangeCounterEvent,
     change_counter,
-    DialogConfig {
-        title: "This will be added to some random".to_string(),
-        input_type: DialogInputType::NUMBER,
-        positive_action: "Randomize!".to_string(),
-        negative_action: "Cancel".to_string()
-    }
+    "dialogs/change_counter.dialog.ron"
 );
 
-fn change_counter(
-    mut person: ResMut<PersonResource>,
-    mut event_reader: EventReader<ChangeCounterEventResult>,
-) {
-    for event in event_reader.read() {
-        let mut rng = rand::thread_rng();
-        let random_number: u32 = rng.gen_range(1..100) + event.result.parse::<u32>().unwrap();
-        let initial = event.event.counter;
-        person.counter = initial + random_number;
+#[derive(Debug)]
+pub enum ChangeCounterError {
+    InvalidNumber(ParseIntError),
+    OutOfBounds(OutOfBounds),
+    Stale(StaleEvent),
+}
+
+impl fmt::Display for ChangeCounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeCounterError::InvalidNumber(error) => write!(f, "not a whole number: {error}"),
+            ChangeCounterError::OutOfBounds(error) => write!(f, "counter out of bounds: {error}"),
+            ChangeCounterError::Stale(error) => write!(f, "counter {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ChangeCounterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChangeCounterError::InvalidNumber(error) => Some(error),
+            ChangeCounterError::OutOfBounds(error) => Some(error),
+            ChangeCounterError::Stale(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseIntError> for ChangeCounterError {
+    fn from(error: ParseIntError) -> Self {
+        ChangeCounterError::InvalidNumber(error)
+    }
+}
+
+impl From<OutOfBounds> for ChangeCounterError {
+    fn from(error: OutOfBounds) -> Self {
+        ChangeCounterError::OutOfBounds(error)
+    }
+}
+
+impl From<StaleEvent> for ChangeCounterError {
+    fn from(error: StaleEvent) -> Self {
+        ChangeCounterError::Stale(error)
     }
 }
+
+fn change_counter(
+    In(event): In<ChangeCounterEventResult>,
+    mut person: PersonEditor,
+    mut notify_writer: EventWriter<Notify>,
+    limits: Res<Limits>,
+    stale_policy: Res<StalePolicy>,
+) -> Result<(), ChangeCounterError> {
+    let added: u32 = event.result.parse()?;
+    let initial = match stale_policy.check(event.event.revision, person.revision)? {
+        Staleness::Current => event.event.counter,
+        Staleness::Rebased => person.counter,
+    };
+    let random_number: u32 = rand::thread_rng().gen_range(1..100);
+    let counter = limits
+        .counter
+        .add(initial, added as i64 + random_number as i64)?;
+
+    person.edit(event.event.clone(), |person| {
+        person.counter = counter;
+    });
+    notify_writer.write(Notify::success(
+        LocalizedText::new("notify-counter-changed").with_arg("counter", counter),
+    ));
+    Ok(())
+}
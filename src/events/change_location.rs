@@ -1,30 +1,35 @@
-use bevy::ecs::{
-    event::{BufferedEvent, EventReader},
-    system::ResMut,
-};
+use bevy::ecs::event::Event;
 
-use crate::{create_event_with_dialog, person_resource::PersonResource};
+use crate::{
+    create_event_with_dialog,
+    events::{error::HandlerError, person_changed::PersonEditor},
+    person_resource::PersonResource,
+};
 
-#[derive(BufferedEvent, Clone)]
+#[derive(Event, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeLocationEvent;
 
+impl From<&PersonResource> for ChangeLocationEvent {
+    fn from(_: &PersonResource) -> Self {
+        ChangeLocationEvent
+    }
+}
+
 create_event_with_dialog!(
     ChangeLocation,
     ChangeLocationEvent,
     change_location,
-    DialogConfig {
-        title: "Location".to_string(),
-        input_type: DialogInputType::TEXT,
-        positive_action: "Change".to_string(),
-        negative_action: "Cancel".to_string()
-    }
+    "dialogs/change_location.dialog.ron",
+    Observer
 );
 
 fn change_location(
-    mut person: ResMut<PersonResource>,
-    mut event_reader: EventReader<ChangeLocationEventResult>,
-) {
-    for event in event_reader.read() {
-        person.location = event.result.clone();
-    }
+    In(event): In<ChangeLocationEventResult>,
+    mut person: PersonEditor,
+) -> Result<(), HandlerError> {
+    person.edit(event.event.clone(), |person| {
+        person.location = event.result;
+    });
+    Ok(())
 }
@@ -1,30 +1,34 @@
-use bevy::ecs::{
-    event::{BufferedEvent, EventReader},
-    system::ResMut,
-};
+use bevy::ecs::event::BufferedEvent;
 
-use crate::{create_event_with_dialog, person_resource::PersonResource};
+use crate::{
+    create_event_with_dialog,
+    events::{error::HandlerError, person_changed::PersonEditor},
+    person_resource::PersonResource,
+};
 
 #[derive(BufferedEvent, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeNameEvent;
 
+impl From<&PersonResource> for ChangeNameEvent {
+    fn from(_: &PersonResource) -> Self {
+        ChangeNameEvent
+    }
+}
+
 create_event_with_dialog!(
     ChangeName,
     ChangeNameEvent,
     change_name,
-    DialogConfig {
-        title: "Name".to_string(),
-        input_type: DialogInputType::TEXT,
-        positive_action: "Change".to_string(),
-        negative_action: "Cancel".to_string()
-    }
+    "dialogs/change_name.dialog.ron"
 );
 
 fn change_name(
-    mut person: ResMut<PersonResource>,
-    mut event_reader: EventReader<ChangeNameEventResult>,
-) {
-    for event in event_reader.read() {
-        person.name = event.result.clone();
-    }
+    In(event): In<ChangeNameEventResult>,
+    mut person: PersonEditor,
+) -> Result<(), HandlerError> {
+    person.edit(event.event.clone(), |person| {
+        person.name = event.result;
+    });
+    Ok(())
 }
@@ -0,0 +1,63 @@
+use bevy::{ecs::component::Mutable, prelude::*};
+
+use crate::events::{bevy_ui_dialogs, egui_dialogs, events::AppEventKind};
+
+/// Draws the dialogs generated by `create_event_with_dialog!`, chosen when constructing
+/// `EventsPlugin`.
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default)]
+pub enum DialogBackend {
+    /// Egui windows, drawn in `EguiPrimaryContextPass`.
+    #[default]
+    Egui,
+    /// `bevy_ui` nodes with a text input and buttons.
+    BevyUi,
+}
+
+impl DialogBackend {
+    /// Adds the systems that draw `D` dialogs.
+    pub fn add_systems<D: DialogWindow>(self, app: &mut App) {
+        match self {
+            DialogBackend::Egui => egui_dialogs::add_systems::<D>(app),
+            DialogBackend::BevyUi => bevy_ui_dialogs::add_systems::<D>(app),
+        }
+    }
+}
+
+/// Whether a `bevy_ui` dialog has keyboard focus, the counterpart of `EguiWantsInput`.
+/// Shortcuts are ignored while it is set.
+#[derive(Resource, Default, Debug)]
+pub struct DialogWantsInput(pub(crate) bool);
+
+impl DialogWantsInput {
+    pub fn get(&self) -> bool {
+        self.0
+    }
+}
+
+/// The open dialog of one event, as generated by `create_event_with_dialog!`.
+///
+/// Backends edit the input and trigger `DialogResponse` with the dialog entity; the generated code
+/// sends the result and closes the dialog.
+pub trait DialogWindow: Component<Mutability = Mutable> {
+    const KIND: AppEventKind;
+
+    fn input(&self) -> &str;
+
+    fn input_mut(&mut self) -> &mut String;
+
+    /// The person changed while the dialog was open and the event was refreshed.
+    fn stale(&self) -> bool;
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DialogAction {
+    Submit,
+    Cancel,
+}
+
+/// Triggered by a backend when the user submits or dismisses a dialog.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DialogResponse {
+    pub dialog: Entity,
+    pub action: DialogAction,
+}
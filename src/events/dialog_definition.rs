@@ -0,0 +1,238 @@
+use std::{borrow::Cow, collections::HashMap, fmt, io};
+
+use bevy::{
+    asset::{
+        AssetLoader, LoadContext,
+        io::{Reader, file::FileAssetReader},
+    },
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{events::events::AppEventKind, localization::LocalizedText, notify::Notify};
+
+/// How the dialog input is filtered while typing.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Deserialize)]
+pub enum DialogInputKind {
+    #[default]
+    Text,
+    /// Digits, `.` and `-` only.
+    Number,
+}
+
+/// A check on the dialog input. The dialog cannot be submitted while one fails.
+#[derive(Clone, Debug, Deserialize)]
+pub enum DialogValidator {
+    /// At most this many characters.
+    MaxLength(usize),
+    /// An integer in `min..=max`.
+    Range(i64, i64),
+}
+
+impl DialogValidator {
+    pub fn check(&self, input: &str) -> Result<(), LocalizedText> {
+        match *self {
+            DialogValidator::MaxLength(max) if input.chars().count() > max => {
+                Err(LocalizedText::new("dialog-invalid-length").with_arg("max", max))
+            }
+            DialogValidator::Range(min, max) => match input.parse::<i64>() {
+                Ok(value) if (min..=max).contains(&value) => Ok(()),
+                Ok(_) => Err(LocalizedText::new("dialog-invalid-range")
+                    .with_arg("min", min)
+                    .with_arg("max", max)),
+                Err(_) => Err(LocalizedText::new("dialog-invalid-number")),
+            },
+            DialogValidator::MaxLength(_) => Ok(()),
+        }
+    }
+}
+
+/// Title, labels and input of a dialog generated by `create_event_with_dialog!`, loaded from a
+/// `.dialog.ron` asset. Texts are Fluent message ids, with optional arguments.
+///
+/// Dialogs read their definition every frame, so edits on disk apply to open dialogs as soon as
+/// the asset is reloaded.
+#[derive(Asset, TypePath, Clone, Debug, Deserialize)]
+pub struct DialogDefinition {
+    pub title: LocalizedText,
+    #[serde(default)]
+    pub input: DialogInputKind,
+    pub positive_action: LocalizedText,
+    pub negative_action: LocalizedText,
+    #[serde(default)]
+    pub validators: Vec<DialogValidator>,
+    /// Placeholder shown while the input is empty.
+    #[serde(default)]
+    pub hint: Option<LocalizedText>,
+    /// Width of the input, the default width when unset.
+    #[serde(default)]
+    pub width: Option<f32>,
+}
+
+impl DialogDefinition {
+    /// Used while the asset of `kind` is loading or failed to load.
+    pub fn fallback(kind: AppEventKind) -> Self {
+        DialogDefinition {
+            title: LocalizedText::new(kind.info().name_id),
+            input: DialogInputKind::Text,
+            positive_action: LocalizedText::new("button-change"),
+            negative_action: LocalizedText::new("button-cancel"),
+            validators: Vec::new(),
+            hint: None,
+            width: None,
+        }
+    }
+
+    /// The first failing validator's message.
+    pub fn validate(&self, input: &str) -> Result<(), LocalizedText> {
+        self.validators
+            .iter()
+            .try_for_each(|validator| validator.check(input))
+    }
+
+    /// Drops the characters `input` does not accept.
+    pub fn filter(&self, input: &str) -> String {
+        match self.input {
+            DialogInputKind::Number => input
+                .chars()
+                .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+                .collect(),
+            DialogInputKind::Text => input.to_string(),
+        }
+    }
+
+    /// Whether `input` can be submitted: not empty and accepted by every validator.
+    pub fn accepts(&self, input: &str) -> bool {
+        !input.is_empty() && self.validate(input).is_ok()
+    }
+}
+
+/// `DialogDefinition` handles by event, filled by the generated event plugins.
+///
+/// Apps without an `AssetServer` read each definition from disk once instead, so inputs supplied
+/// ahead of time are checked against the same validators.
+#[derive(Resource, Default)]
+pub struct DialogDefinitions {
+    handles: HashMap<AppEventKind, Handle<DialogDefinition>>,
+    read: HashMap<AppEventKind, DialogDefinition>,
+}
+
+impl DialogDefinitions {
+    pub fn insert(&mut self, kind: AppEventKind, handle: Handle<DialogDefinition>) {
+        self.handles.insert(kind, handle);
+    }
+
+    /// Reads the definition of `kind` from `path` in the asset folder, keeping the fallback if
+    /// that fails.
+    pub fn read(&mut self, kind: AppEventKind, path: &str) {
+        let path = FileAssetReader::new("assets").root_path().join(path);
+        let definition = std::fs::read_to_string(&path)
+            .map_err(DialogDefinitionError::Io)
+            .and_then(|source| ron::from_str(&source).map_err(DialogDefinitionError::Parse));
+        match definition {
+            Ok(definition) => {
+                self.read.insert(kind, definition);
+            }
+            Err(error) => warn!("Could not read dialog {}: {error}", path.display()),
+        }
+    }
+
+    /// The loaded definition of `kind`, or its fallback.
+    pub fn get<'a>(
+        &'a self,
+        kind: AppEventKind,
+        assets: &'a Assets<DialogDefinition>,
+    ) -> Cow<'a, DialogDefinition> {
+        self.find(kind, Some(assets))
+    }
+
+    /// Like `get`, for apps that may have no `Assets<DialogDefinition>`.
+    pub fn find<'a>(
+        &'a self,
+        kind: AppEventKind,
+        assets: Option<&'a Assets<DialogDefinition>>,
+    ) -> Cow<'a, DialogDefinition> {
+        let loaded = self
+            .handles
+            .get(&kind)
+            .and_then(|handle| assets?.get(handle))
+            .or_else(|| self.read.get(&kind));
+        match loaded {
+            Some(definition) => Cow::Borrowed(definition),
+            None => Cow::Owned(DialogDefinition::fallback(kind)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DialogDefinitionError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for DialogDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DialogDefinitionError::Io(error) => write!(f, "{error}"),
+            DialogDefinitionError::Parse(error) => write!(f, "invalid dialog definition: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DialogDefinitionError {}
+
+#[derive(Default)]
+struct DialogDefinitionLoader;
+
+impl AssetLoader for DialogDefinitionLoader {
+    type Asset = DialogDefinition;
+    type Settings = ();
+    type Error = DialogDefinitionError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(DialogDefinitionError::Io)?;
+        ron::de::from_bytes(&bytes).map_err(DialogDefinitionError::Parse)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["dialog.ron"]
+    }
+}
+
+/// Registers `DialogDefinition` assets. Added by `EventsPlugin` when the app has an
+/// `AssetServer`; headless apps never show dialogs.
+pub struct DialogDefinitionPlugin;
+
+impl Plugin for DialogDefinitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<DialogDefinition>()
+            .init_asset_loader::<DialogDefinitionLoader>()
+            .add_systems(Update, report_dialog_reloads);
+    }
+}
+
+fn report_dialog_reloads(
+    mut asset_events: EventReader<AssetEvent<DialogDefinition>>,
+    mut notify_writer: EventWriter<Notify>,
+    asset_server: Res<AssetServer>,
+) {
+    for event in asset_events.read() {
+        if let AssetEvent::Modified { id } = event
+            && let Some(path) = asset_server.get_path(*id)
+        {
+            info!("Reloaded dialog {path}");
+            notify_writer.write(Notify::info(
+                LocalizedText::new("notify-dialog-reloaded").with_arg("path", path.to_string()),
+            ));
+        }
+    }
+}
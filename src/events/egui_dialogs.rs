@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts, EguiPrimaryContextPass,
+    egui::{self, Color32, Frame, RichText, Vec2},
+};
+
+use crate::{
+    events::{
+        dialog_backend::{DialogAction, DialogResponse, DialogWindow},
+        dialog_definition::{DialogDefinition, DialogDefinitions},
+        presence::EditorPresence,
+    },
+    localization::Localization,
+};
+
+const WARNING: Color32 = Color32::from_rgb(230, 180, 80);
+const ERROR: Color32 = Color32::from_rgb(230, 80, 80);
+
+pub(crate) fn add_systems<D: DialogWindow>(app: &mut App) {
+    app.add_systems(
+        EguiPrimaryContextPass,
+        dialog_window::<D>.run_if(any_with_component::<D>),
+    );
+}
+
+fn dialog_window<D: DialogWindow>(
+    mut commands: Commands,
+    mut window: Single<(Entity, &mut D)>,
+    mut contexts: EguiContexts,
+    localization: Res<Localization>,
+    presence: Res<EditorPresence>,
+    definitions: Res<DialogDefinitions>,
+    assets: Res<Assets<DialogDefinition>>,
+) {
+    let ctx = contexts.ctx_mut().unwrap();
+    let (dialog, window) = &mut *window;
+    let definition = definitions.get(D::KIND, &assets);
+    let title = localization.format(&definition.title);
+    let editors = presence.header(D::KIND);
+
+    let mut action = None;
+    let mut open = true;
+    egui::Window::new(title.clone())
+        .id(egui::Id::new(("Dialog Window", D::KIND)))
+        .open(&mut open)
+        .interactable(true)
+        .collapsible(false)
+        .resizable(false)
+        .title_bar(false)
+        .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+        .show(ctx, |ui| {
+            Frame::new().show(ui, |ui| {
+                ui.heading(title);
+                if let Some(editors) = &editors {
+                    ui.colored_label(WARNING, localization.format(editors));
+                }
+                if window.stale() {
+                    ui.colored_label(WARNING, localization.text("dialog-stale"));
+                }
+                ui.add_space(16.0);
+
+                let mut new_input = window.input().to_string();
+                let mut text_edit = egui::TextEdit::singleline(&mut new_input);
+                if let Some(hint) = &definition.hint {
+                    text_edit = text_edit.hint_text(localization.format(hint));
+                }
+                if let Some(width) = definition.width {
+                    text_edit = text_edit.desired_width(width);
+                }
+                ui.add(text_edit);
+
+                let new_input = definition.filter(&new_input);
+                if !new_input.is_empty()
+                    && let Err(error) = definition.validate(&new_input)
+                {
+                    ui.colored_label(ERROR, localization.format(&error));
+                }
+                ui.add_space(16.0);
+
+                let accepted = definition.accepts(&new_input);
+                *window.input_mut() = new_input;
+
+                ui.horizontal(|ui| {
+                    let positive = localization.format(&definition.positive_action);
+                    if dialog_button(ui, positive, Color32::from_rgb(70, 140, 70)).clicked()
+                        && accepted
+                    {
+                        action = Some(DialogAction::Submit);
+                    }
+
+                    ui.add_space(12.0);
+
+                    let negative = localization.format(&definition.negative_action);
+                    if dialog_button(ui, negative, Color32::from_rgb(200, 50, 70)).clicked() {
+                        action = Some(DialogAction::Cancel);
+                    }
+                });
+            });
+        });
+
+    if !open {
+        action = Some(DialogAction::Cancel);
+    }
+    if let Some(action) = action {
+        commands.trigger(DialogResponse {
+            dialog: *dialog,
+            action,
+        });
+    }
+}
+
+fn dialog_button(ui: &mut egui::Ui, label: String, fill: Color32) -> egui::Response {
+    ui.add(
+        egui::Button::new(RichText::new(label).color(Color32::WHITE))
+            .fill(fill)
+            .corner_radius(10.0)
+            .min_size(egui::Vec2::new(120.0, 36.0)),
+    )
+}
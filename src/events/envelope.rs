@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+/// Layout version of serialized events; bump it whenever an event or result changes shape.
+pub const EVENT_FORMAT_VERSION: u32 = 1;
+
+/// A serialized `AppEvent`, `${Event}Result` or history export stamped with the format it was
+/// written in, e.g. `{"version":1,"event":{"type":"GrowOlder","data":null}}`. IPC requests and
+/// relay edits flatten it into the message.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EventEnvelope<E> {
+    pub version: u32,
+    pub event: E,
+}
+
+impl<E> EventEnvelope<E> {
+    pub fn new(event: E) -> Self {
+        EventEnvelope {
+            version: EVENT_FORMAT_VERSION,
+            event,
+        }
+    }
+
+    /// The wrapped event, if it was written in the current format.
+    pub fn into_event(self) -> Result<E, String> {
+        if self.version != EVENT_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported event format version {} (expected {EVENT_FORMAT_VERSION})",
+                self.version
+            ));
+        }
+        Ok(self.event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::color::{Color, Srgba};
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::events::{
+        change_color::OpenColorPickerEvent, change_counter::ChangeCounterEventResult,
+        change_location::ChangeLocationEventResult, change_name::ChangeNameEventResult,
+        events::AppEvent,
+    };
+
+    /// Deserializes `value` as `T` inside an envelope and checks it serializes back unchanged.
+    fn round_trip<T: Serialize + DeserializeOwned>(value: Value) -> T {
+        let line = json!({"version": EVENT_FORMAT_VERSION, "event": value}).to_string();
+        let envelope: EventEnvelope<T> = serde_json::from_str(&line).unwrap();
+        let event = envelope.into_event().unwrap();
+        let written = serde_json::to_value(EventEnvelope::new(&event)).unwrap();
+        assert_eq!(written["event"], value);
+        event
+    }
+
+    #[test]
+    fn every_event_round_trips() {
+        let events = [
+            json!({"type": "ChangeName", "data": null}),
+            json!({"type": "GrowOlder", "data": null}),
+            json!({"type": "ChangeLocation", "data": null}),
+            json!({"type": "ChangeCounter", "data": {"counter": 3, "revision": 7}}),
+        ];
+        for event in events {
+            let kind = round_trip::<AppEvent>(event.clone()).kind();
+            assert_eq!(kind.info().name, event["type"]);
+        }
+    }
+
+    #[test]
+    fn color_picker_event_keeps_its_color() {
+        let color = Color::Srgba(Srgba::new(0.25, 0.5, 0.75, 1.0));
+        let event = AppEvent::OpenColorPicker(OpenColorPickerEvent {
+            color,
+            revision: None,
+        });
+
+        let AppEvent::OpenColorPicker(read) = round_trip(serde_json::to_value(&event).unwrap())
+        else {
+            panic!("read back a different event");
+        };
+        assert_eq!(read.color, color);
+        assert_eq!(read.revision, None);
+    }
+
+    #[test]
+    fn every_result_round_trips() {
+        round_trip::<ChangeNameEventResult>(json!({"event": null, "result": "Ada"}));
+        round_trip::<ChangeLocationEventResult>(json!({"event": null, "result": "Paris"}));
+        round_trip::<ChangeCounterEventResult>(json!({
+            "event": {"counter": 3, "revision": null},
+            "result": "5",
+        }));
+    }
+
+    #[test]
+    fn other_versions_are_refused() {
+        let line = json!({
+            "version": EVENT_FORMAT_VERSION + 1,
+            "event": {"type": "GrowOlder", "data": null},
+        });
+        let envelope: EventEnvelope<AppEvent> = serde_json::from_value(line).unwrap();
+        assert!(envelope.into_event().is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use std::{fmt, sync::Arc};
+
+use bevy::{ecs::event::EventId, prelude::*};
+
+use crate::{
+    events::{
+        events::AppEvent,
+        source::RoutedEvents,
+        stale::{StaleEvent, StalePolicy},
+    },
+    localization::LocalizedText,
+    notify::Notify,
+    person_resource::PersonResource,
+};
+
+/// Error returned by event handlers. Any `std::error::Error` converts into it, so handlers can
+/// use `?`.
+#[derive(Debug)]
+pub struct HandlerError {
+    message: String,
+    stale: Option<StaleEvent>,
+    localized: Option<LocalizedText>,
+}
+
+impl HandlerError {
+    pub fn new(message: impl Into<String>) -> Self {
+        HandlerError {
+            message: message.into(),
+            stale: None,
+            localized: None,
+        }
+    }
+
+    /// An error whose toast is shown in the active locale, e.g. a rejected dialog input.
+    pub fn localized(text: LocalizedText) -> Self {
+        HandlerError {
+            message: text.to_string(),
+            stale: None,
+            localized: Some(text),
+        }
+    }
+
+    /// Set when the error, or one of its sources, is a `StaleEvent`.
+    pub fn stale(&self) -> Option<&StaleEvent> {
+        self.stale.as_ref()
+    }
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl<E: std::error::Error + 'static> From<E> for HandlerError {
+    fn from(error: E) -> Self {
+        let stale = std::iter::successors(
+            Some(&error as &(dyn std::error::Error + 'static)),
+            |error| error.source(),
+        )
+        .find_map(|error| error.downcast_ref::<StaleEvent>())
+        .cloned();
+
+        HandlerError {
+            message: error.to_string(),
+            stale,
+            localized: None,
+        }
+    }
+}
+
+/// Emitted when an event handler returns an error.
+#[derive(BufferedEvent, Clone)]
+pub struct EventFailed {
+    pub event: AppEvent,
+    pub error: String,
+}
+
+type ErasedHandler<T> = dyn Fn(&mut World, T) -> Result<(), HandlerError> + Send + Sync;
+
+/// A handler registered as a one-shot system taking `In<T>`, with its error type erased.
+pub struct RegisteredHandler<T>(Arc<ErasedHandler<T>>);
+
+impl<T> Clone for RegisteredHandler<T> {
+    fn clone(&self) -> Self {
+        RegisteredHandler(self.0.clone())
+    }
+}
+
+impl<T: Send + 'static> RegisteredHandler<T> {
+    pub fn register<E, M>(
+        world: &mut World,
+        handler: impl IntoSystem<In<T>, Result<(), E>, M> + 'static,
+    ) -> Self
+    where
+        E: Into<HandlerError> + 'static,
+    {
+        let id = world.register_system(handler);
+        RegisteredHandler(Arc::new(move |world: &mut World, input: T| {
+            match world.run_system_with(id, input) {
+                Ok(result) => result.map_err(Into::into),
+                Err(error) => Err(HandlerError::new(error.to_string())),
+            }
+        }))
+    }
+
+    /// Runs the handler for `event`, reporting a failure as `EventFailed`, a toast and a log line.
+    ///
+    /// A `StaleEvent` under `StalePolicy::Prompt` is not a failure, it only shows a warning.
+    ///
+    /// `routed` is the id of the routed event being handled, changes made by the handler carry it.
+    pub fn run(
+        &self,
+        world: &mut World,
+        input: T,
+        event: AppEvent,
+        routed: Option<EventId<AppEvent>>,
+    ) -> Result<(), HandlerError> {
+        world.resource_mut::<RoutedEvents>().set_handling(routed);
+        let result = (self.0)(world, input);
+        world.resource_mut::<RoutedEvents>().set_handling(None);
+
+        let error = match result {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+
+        let report = match error.stale() {
+            Some(stale) if stale.policy == StalePolicy::Prompt => {
+                world.run_system_cached_with(report_stale_event, event)
+            }
+            _ => world.run_system_cached_with(
+                report_handler_error,
+                (event, error.to_string(), error.localized.clone()),
+            ),
+        };
+        if let Err(report_error) = report {
+            error!("Could not report handler error: {report_error}");
+        }
+        Err(error)
+    }
+}
+
+/// Reports a failure detected outside a registered handler, e.g. by a hand-written dialog, the
+/// same way `RegisteredHandler::run` does.
+pub fn report_failure(commands: &mut Commands, event: AppEvent, error: impl Into<HandlerError>) {
+    let error = error.into();
+    commands.run_system_cached_with(
+        report_handler_error,
+        (event, error.to_string(), error.localized),
+    );
+}
+
+fn report_stale_event(In(event): In<AppEvent>, mut notify_writer: EventWriter<Notify>) {
+    info!("{:?} is stale, asking again", event.kind());
+    notify_writer.write(Notify::warning(
+        LocalizedText::new("notify-event-stale")
+            .with_text_arg("event", LocalizedText::new(event.kind().info().name_id)),
+    ));
+}
+
+fn report_handler_error(
+    In((event, error, localized)): In<(AppEvent, String, Option<LocalizedText>)>,
+    mut failed_writer: EventWriter<EventFailed>,
+    mut notify_writer: EventWriter<Notify>,
+    person: Res<PersonResource>,
+) {
+    error!("{:?} failed: {error}", event.kind());
+    let kind = event.kind();
+    let message = LocalizedText::new("notify-handler-failed")
+        .with_text_arg("event", LocalizedText::new(kind.info().name_id));
+    let notify = Notify::error(match localized {
+        Some(localized) => message.with_text_arg("error", localized),
+        None => message.with_arg("error", error.clone()),
+    });
+    // Dialog events reopen their dialog instead.
+    notify_writer.write(if kind.info().needs_dialog {
+        notify
+    } else {
+        notify.with_action(LocalizedText::new("button-retry"), kind.construct(&person))
+    });
+    failed_writer.write(EventFailed { event, error });
+}
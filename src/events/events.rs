@@ -7,9 +7,166 @@ use crate::events::grow_older::*;
 
 create_events_plugin!(
     EventsPlugin,
-    ChangeName,
-    GrowOlder,
-    ChangeLocation,
-    OpenColorPicker,
-    ChangeCounter,
+    ChangeName: Mutating(Name) => "Ctrl+N",
+    GrowOlder: Mutating(Age) if can_grow_older => "Ctrl+G",
+    ChangeLocation: Mutating(Location) => "Ctrl+L",
+    OpenColorPicker: Mutating(Color) => "Ctrl+K",
+    ChangeCounter: Mutating(Counter) => "Ctrl+R",
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::{
+            dialog_backend::{DialogAction, DialogResponse, DialogWindow},
+            error::EventFailed,
+            person_changed::PersonChangedPlugin,
+            prefill::PrefilledInputs,
+        },
+        person_resource::PersonResource,
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, EventsPlugin::default(), PersonChangedPlugin))
+            .insert_resource(PersonResource::default());
+        app.update();
+        app
+    }
+
+    /// Calls `app.update()` until `done` holds, returning how many calls it took.
+    fn frames_until(app: &mut App, mut done: impl FnMut(&mut World) -> bool) -> u32 {
+        let mut frames = 0;
+        while !done(app.world_mut()) {
+            assert!(frames < 10, "never happened");
+            app.update();
+            frames += 1;
+        }
+        frames
+    }
+
+    /// Routes an event of `kind`, submits `input` in its dialog outside `Update` the way dialog
+    /// backends do, and counts the frames until the dialog opened and until `applied` holds.
+    fn frames_through_dialog<W: DialogWindow>(
+        kind: AppEventKind,
+        input: &str,
+        applied: impl Fn(&PersonResource) -> bool,
+    ) -> (u32, u32) {
+        let mut app = app();
+        let event = kind.construct(app.world().resource::<PersonResource>());
+        app.world_mut().write_event(event);
+
+        let mut dialogs = app.world_mut().query_filtered::<Entity, With<W>>();
+        let opened = frames_until(&mut app, |world| dialogs.single(world).is_ok());
+        let dialog = dialogs.single(app.world()).unwrap();
+        app.world_mut()
+            .get_mut::<W>(dialog)
+            .unwrap()
+            .input_mut()
+            .push_str(input);
+        app.world_mut().trigger(DialogResponse {
+            dialog,
+            action: DialogAction::Submit,
+        });
+
+        let submitted = frames_until(&mut app, |world| {
+            applied(world.resource::<PersonResource>())
+        });
+        (opened, submitted)
+    }
+
+    /// Routes an event of `kind` with a pre-filled result and counts the frames until `applied`
+    /// holds.
+    fn frames_with_prefilled_input(
+        kind: AppEventKind,
+        input: &str,
+        applied: impl Fn(&PersonResource) -> bool,
+    ) -> u32 {
+        let mut app = app();
+        let event = kind.construct(app.world().resource::<PersonResource>());
+        app.world_mut()
+            .resource_mut::<PrefilledInputs>()
+            .push(kind, input.to_string());
+        app.world_mut().write_event(event);
+        frames_until(&mut app, |world| {
+            applied(world.resource::<PersonResource>())
+        })
+    }
+
+    #[test]
+    fn observer_results_apply_before_buffered_results() {
+        let (_, buffered) = frames_through_dialog::<ChangeNameEventWindow>(
+            AppEventKind::ChangeName,
+            "Ada",
+            |person| person.name == "Ada",
+        );
+        let (_, observer) = frames_through_dialog::<ChangeLocationEventWindow>(
+            AppEventKind::ChangeLocation,
+            "Paris",
+            |person| person.location == "Paris",
+        );
+
+        assert!(
+            observer < buffered,
+            "observer took {observer} frames, buffered {buffered}"
+        );
+    }
+
+    #[test]
+    fn frames_from_route_to_handler() {
+        // `ChangeName` is buffered, `ChangeLocation` an observer. Both open their dialog in the
+        // frame they are routed in.
+        assert_eq!(
+            frames_through_dialog::<ChangeNameEventWindow>(
+                AppEventKind::ChangeName,
+                "Ada",
+                |person| person.name == "Ada",
+            ),
+            (1, 1)
+        );
+        assert_eq!(
+            frames_through_dialog::<ChangeLocationEventWindow>(
+                AppEventKind::ChangeLocation,
+                "Paris",
+                |person| person.location == "Paris",
+            ),
+            (1, 0)
+        );
+
+        // Without a dialog the whole path runs in the routing frame.
+        assert_eq!(
+            frames_with_prefilled_input(AppEventKind::ChangeName, "Ada", |person| {
+                person.name == "Ada"
+            }),
+            1
+        );
+        assert_eq!(
+            frames_with_prefilled_input(AppEventKind::ChangeLocation, "Paris", |person| {
+                person.location == "Paris"
+            }),
+            1
+        );
+    }
+
+    #[test]
+    fn prefilled_inputs_are_validated() {
+        let mut app = app();
+        let name = app.world().resource::<PersonResource>().name.clone();
+        app.world_mut()
+            .resource_mut::<PrefilledInputs>()
+            .push(AppEventKind::ChangeName, "A".repeat(41));
+        app.world_mut()
+            .write_event(AppEvent::ChangeName(ChangeNameEvent));
+        app.update();
+
+        assert_eq!(app.world().resource::<PersonResource>().name, name);
+        let failed: Vec<_> = app
+            .world()
+            .resource::<Events<EventFailed>>()
+            .iter_current_update_events()
+            .map(|failed| failed.event.kind())
+            .collect();
+        assert_eq!(failed, [AppEventKind::ChangeName]);
+    }
+}
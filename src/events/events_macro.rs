@@ -3,64 +3,71 @@
 
 #[macro_export]
 macro_rules! create_event_with_dialog {
-    ($plugin_name:ident, $event_type:ident, $handler_fn:ident, $config:expr) => {
+    ($plugin_name:ident, $event_type:ident, $handler_fn:ident, $path:literal) => {
+        $crate::create_event_with_dialog!($plugin_name, $event_type, $handler_fn, $path, Buffered);
+    };
+
+    ($plugin_name:ident, $event_type:ident, $handler_fn:ident, $path:literal, Buffered) => {
         use bevy::prelude::*;
-        use bevy_egui::{
-            EguiPrimaryContextPass,
-            EguiContexts,
-            egui::{self, Frame, Color32, RichText, Vec2},
-        };
 
-        pub enum DialogInputType {
-            NUMBER,
-            TEXT,
+        #[derive(BufferedEvent, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct ${concat($event_type, Result)} {
+            event: $event_type,
+            result: String,
+            #[cfg_attr(feature = "serde", serde(skip))]
+            routed: Option<bevy::ecs::event::EventId<$crate::events::events::AppEvent>>,
         }
 
-        pub struct DialogConfig {
-            title: String,
-            input_type: DialogInputType,
-            positive_action: String,
-            negative_action: String,
+        $crate::create_event!(@buffered_dispatcher $plugin_name, $event_type);
+
+        #[derive(bevy::ecs::system::SystemParam)]
+        pub struct ${concat($event_type, ResultSender)}<'w, 's> {
+            commands: Commands<'w, 's>,
+            writer: EventWriter<'w, ${concat($event_type, Result)}>,
+            submitted_writer: EventWriter<'w, $crate::events::prefill::DialogSubmitted>,
+            definitions: Res<'w, $crate::events::dialog_definition::DialogDefinitions>,
+            assets: Option<Res<'w, Assets<$crate::events::dialog_definition::DialogDefinition>>>,
         }
 
-        #[derive(BufferedEvent)]
-        pub struct ${concat($event_type, Result)} {
-            event: $event_type,
-            result: String
+        impl ${concat($event_type, ResultSender)}<'_, '_> {
+            fn send(&mut self, result: ${concat($event_type, Result)}) {
+                if !accepts_input(&mut self.commands, &self.definitions, self.assets.as_deref(), &result) {
+                    return;
+                }
+                self.submitted_writer.write($crate::events::prefill::DialogSubmitted {
+                    kind: <$event_type as $crate::events::registry::RoutedEvent>::KIND,
+                    input: result.result.clone(),
+                });
+                self.writer.write(result);
+            }
         }
 
         pub struct ${concat($plugin_name, Plugin)};
 
-        #[derive(Component)]
-        pub struct ${concat($event_type, Window)} {
-            event: $event_type,
-            input: String,
-        }
-
         impl Plugin for ${concat($plugin_name, Plugin)} {
             fn build(&self, app: &mut App) {
+                let handler = $crate::events::error::RegisteredHandler::register(app.world_mut(), $handler_fn);
+
                 app
+                    .insert_resource(${concat($plugin_name, Handler)}(handler))
                     .add_event::<${concat($event_type, Result)}>()
                     .add_event::<$event_type>()
                     .add_systems(
                         Update,
-                        open_dialog.run_if(has_open_dialog_events)
+                        open_dialog
+                            .run_if(has_open_dialog_events)
+                            .in_set($crate::events::sets::EventsSet::OpenDialogs)
                     )
                     .add_systems(
                         Update,
-                        $handler_fn.run_if(has_result_events)
-                    )
-                    .add_systems(
-                        EguiPrimaryContextPass,
-                        dialog_window.run_if(window_active)
+                        run_handler
+                            .run_if(has_result_events)
+                            .in_set($crate::events::sets::EventsSet::Handle)
                     );
-            }
-        }
 
-        fn window_active(
-            query: Query<Entity, With<${concat($event_type, Window)}>>,
-        ) -> bool {
-            !query.is_empty()
+                add_dialog_systems(app);
+            }
         }
 
         fn has_result_events(
@@ -78,96 +85,341 @@ macro_rules! create_event_with_dialog {
         fn open_dialog(
             mut commands: Commands,
             mut event_reader: EventReader<$event_type>,
+            mut prefilled: ResMut<$crate::events::prefill::PrefilledInputs>,
+            mut routed_events: ResMut<$crate::events::source::RoutedEvents>,
+            mut result_sender: ${concat($event_type, ResultSender)},
             query: Query<Entity, With<${concat($event_type, Window)}>>,
         ) {
             for event in event_reader.read() {
-                for entity in query.iter() {
-                    commands.entity(entity).despawn();
+                let routed = routed_events.take(<$event_type as $crate::events::registry::RoutedEvent>::KIND);
+                open_or_submit(&mut commands, &mut prefilled, &mut result_sender, &query, event, routed);
+            }
+        }
+
+        fn run_handler(
+            mut commands: Commands,
+            mut event_reader: EventReader<${concat($event_type, Result)}>,
+            handler: Res<${concat($plugin_name, Handler)}>,
+        ) {
+            for result in event_reader.read() {
+                queue_handler(&mut commands, &handler, result);
+            }
+        }
+
+        $crate::create_event_with_dialog!(@dialog $plugin_name, $event_type, $path);
+    };
+
+    ($plugin_name:ident, $event_type:ident, $handler_fn:ident, $path:literal, Observer) => {
+        use bevy::prelude::*;
+
+        #[derive(Event, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct ${concat($event_type, Result)} {
+            event: $event_type,
+            result: String,
+            #[cfg_attr(feature = "serde", serde(skip))]
+            routed: Option<bevy::ecs::event::EventId<$crate::events::events::AppEvent>>,
+        }
+
+        $crate::create_event!(@observer_dispatcher $plugin_name, $event_type);
+
+        #[derive(bevy::ecs::system::SystemParam)]
+        pub struct ${concat($event_type, ResultSender)}<'w, 's> {
+            commands: Commands<'w, 's>,
+            submitted_writer: EventWriter<'w, $crate::events::prefill::DialogSubmitted>,
+            definitions: Res<'w, $crate::events::dialog_definition::DialogDefinitions>,
+            assets: Option<Res<'w, Assets<$crate::events::dialog_definition::DialogDefinition>>>,
+        }
+
+        impl ${concat($event_type, ResultSender)}<'_, '_> {
+            fn send(&mut self, result: ${concat($event_type, Result)}) {
+                if !accepts_input(&mut self.commands, &self.definitions, self.assets.as_deref(), &result) {
+                    return;
                 }
-                commands.spawn(${concat($event_type, Window)} {
-                    event: event.to_owned(),
-                    input: "".to_string(),
+                self.submitted_writer.write($crate::events::prefill::DialogSubmitted {
+                    kind: <$event_type as $crate::events::registry::RoutedEvent>::KIND,
+                    input: result.result.clone(),
                 });
+                self.commands.trigger(result);
+            }
+        }
+
+        pub struct ${concat($plugin_name, Plugin)};
+
+        impl Plugin for ${concat($plugin_name, Plugin)} {
+            fn build(&self, app: &mut App) {
+                let handler = $crate::events::error::RegisteredHandler::register(app.world_mut(), $handler_fn);
+
+                app.insert_resource(${concat($plugin_name, Handler)}(handler))
+                    .add_observer(open_dialog)
+                    .add_observer(run_handler);
+
+                add_dialog_systems(app);
             }
         }
 
-        fn dialog_window(
+        fn run_handler(
+            trigger: On<${concat($event_type, Result)}>,
             mut commands: Commands,
-            mut window: Single<(Entity, &mut ${concat($event_type, Window)})>,
-            mut contexts: EguiContexts,
-            mut event_writer: EventWriter<${concat($event_type, Result)}>
+            handler: Res<${concat($plugin_name, Handler)}>,
         ) {
-            let ctx = contexts.ctx_mut().unwrap();
-            let config: DialogConfig = $config;
-
-            let mut open = true;
-            egui::Window::new(config.title.clone())
-                .open(&mut open)
-                .interactable(true)
-                .collapsible(false)
-                .resizable(false)
-                .title_bar(false)
-                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
-                .show(ctx, |ui| {
-                    Frame::new().show(ui, |ui| {
-                        ui.heading(config.title);
-                        ui.add_space(16.0);
-
-                        let mut new_input = window.1.input.clone();
-                        ui.text_edit_singleline(&mut new_input);
-
-                        let new_input = match config.input_type {
-                            DialogInputType::NUMBER => new_input.chars()
-                                .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
-                                .collect(),
-                            DialogInputType::TEXT => new_input,
-                        };
-
-                        ui.add_space(16.0);
-
-                        window.1.input = new_input.to_string();
-
-                        ui.horizontal(|ui| {
-                            if ui
-                                .add(
-                                    egui::Button::new(
-                                        RichText::new(config.positive_action).color(Color32::from_rgb(255, 255, 255)),
-                                    )
-                                    .fill(Color32::from_rgb(70, 140, 70))
-                                    .corner_radius(10.0)
-                                    .min_size(egui::Vec2::new(120.0, 36.0)),
-                                )
-                                .clicked()
-                                && !new_input.is_empty()
-                            {
-                                event_writer.write(${concat($event_type, Result)} {
-                                    event: window.1.event.clone(),
-                                    result: new_input
-                                });
-                                commands.entity(window.0).despawn();
-                            }
-
-                            ui.add_space(12.0);
-
-                            if ui
-                                .add(
-                                    egui::Button::new(
-                                        RichText::new(config.negative_action).color(Color32::from_rgb(255, 255, 255)),
-                                    )
-                                    .fill(Color32::from_rgb(200, 50, 70))
-                                    .corner_radius(10.0)
-                                    .min_size(egui::Vec2::new(120.0, 36.0)),
-                                )
-                                .clicked()
-                            {
-                                commands.entity(window.0).despawn();
-                            }
-                        });
-                    });
+            queue_handler(&mut commands, &handler, trigger.event());
+        }
+
+        fn open_dialog(
+            trigger: On<$event_type>,
+            mut commands: Commands,
+            mut prefilled: ResMut<$crate::events::prefill::PrefilledInputs>,
+            mut routed_events: ResMut<$crate::events::source::RoutedEvents>,
+            mut result_sender: ${concat($event_type, ResultSender)},
+            query: Query<Entity, With<${concat($event_type, Window)}>>,
+        ) {
+            let routed = routed_events.take(<$event_type as $crate::events::registry::RoutedEvent>::KIND);
+            open_or_submit(
+                &mut commands,
+                &mut prefilled,
+                &mut result_sender,
+                &query,
+                trigger.event(),
+                routed,
+            );
+        }
+
+        $crate::create_event_with_dialog!(@dialog $plugin_name, $event_type, $path);
+    };
+
+    (@dialog $plugin_name:ident, $event_type:ident, $path:literal) => {
+        impl $crate::events::registry::EventDialog for $event_type {
+            const NEEDS_DIALOG: bool = true;
+        }
+
+        #[derive(Resource)]
+        struct ${concat($plugin_name, Handler)}(
+            $crate::events::error::RegisteredHandler<${concat($event_type, Result)}>
+        );
+
+        /// Runs the handler once commands apply; a failed result reopens the dialog with its input.
+        ///
+        /// Stale results are dropped under `StalePolicy::Reject` and reopened against the current
+        /// person under `StalePolicy::Prompt`.
+        fn queue_handler(
+            commands: &mut Commands,
+            handler: &${concat($plugin_name, Handler)},
+            result: &${concat($event_type, Result)},
+        ) {
+            let handler = handler.0.clone();
+            let result = result.clone();
+            commands.queue(move |world: &mut World| {
+                let event = result.event.clone();
+                let input = result.result.clone();
+                let routed = result.routed;
+                let Err(error) = handler.run(world, result, event.clone().into(), routed) else {
+                    return;
+                };
+
+                match error.stale().map(|stale| stale.policy) {
+                    None => reopen_dialog(world, event, input, false, routed),
+                    Some($crate::events::stale::StalePolicy::Reject) => {}
+                    Some(_) => {
+                        // Inside a transaction the handler compares against the staged person.
+                        let person = world
+                            .resource::<$crate::events::transaction::Transaction>()
+                            .staged()
+                            .unwrap_or_else(|| {
+                                world.resource::<$crate::person_resource::PersonResource>()
+                            });
+                        let event = <$event_type>::from(person);
+                        reopen_dialog(world, event, input, true, routed);
+                    }
+                }
+            });
+        }
+
+        /// Checks an input against the dialog definition before it becomes the result, also when
+        /// it was pre-filled by a script, the scheduler, a rule or an IPC client and no dialog was
+        /// shown. A rejected input is reported like a handler error.
+        fn accepts_input(
+            commands: &mut Commands,
+            definitions: &$crate::events::dialog_definition::DialogDefinitions,
+            assets: Option<&Assets<$crate::events::dialog_definition::DialogDefinition>>,
+            result: &${concat($event_type, Result)},
+        ) -> bool {
+            let kind = <$event_type as $crate::events::registry::RoutedEvent>::KIND;
+            match definitions.find(kind, assets).validate(&result.result) {
+                Ok(()) => true,
+                Err(error) => {
+                    $crate::events::error::report_failure(
+                        commands,
+                        result.event.clone().into(),
+                        $crate::events::error::HandlerError::localized(error),
+                    );
+                    false
+                }
+            }
+        }
+
+        fn reopen_dialog(
+            world: &mut World,
+            event: $event_type,
+            input: String,
+            stale: bool,
+            routed: Option<bevy::ecs::event::EventId<$crate::events::events::AppEvent>>,
+        ) {
+            let open: Vec<Entity> = world
+                .query_filtered::<Entity, With<${concat($event_type, Window)}>>()
+                .iter(world)
+                .collect();
+            for entity in open {
+                world.despawn(entity);
+            }
+
+            world.spawn((
+                ${concat($event_type, Window)} { event, input, stale, routed },
+                $crate::events::presence::DialogOpen(
+                    <$event_type as $crate::events::registry::RoutedEvent>::KIND,
+                ),
+            ));
+        }
+
+        #[derive(Component)]
+        pub struct ${concat($event_type, Window)} {
+            event: $event_type,
+            input: String,
+            /// The person changed while the dialog was open and the event was refreshed.
+            stale: bool,
+            /// The id on `EventRouted` of the event that opened the dialog.
+            routed: Option<bevy::ecs::event::EventId<$crate::events::events::AppEvent>>,
+        }
+
+        impl $crate::events::dialog_backend::DialogWindow for ${concat($event_type, Window)} {
+            const KIND: $crate::events::events::AppEventKind =
+                <$event_type as $crate::events::registry::RoutedEvent>::KIND;
+
+            fn input(&self) -> &str {
+                &self.input
+            }
+
+            fn input_mut(&mut self) -> &mut String {
+                &mut self.input
+            }
+
+            fn stale(&self) -> bool {
+                self.stale
+            }
+        }
+
+        fn add_dialog_systems(app: &mut App) {
+            let kind = <$event_type as $crate::events::registry::RoutedEvent>::KIND;
+            let handle = app
+                .world()
+                .get_resource::<AssetServer>()
+                .map(|asset_server| asset_server.load($path));
+            let mut definitions = app
+                .world_mut()
+                .resource_mut::<$crate::events::dialog_definition::DialogDefinitions>();
+            match handle {
+                Some(handle) => definitions.insert(kind, handle),
+                None => definitions.read(kind, $path),
+            }
+
+            let backend = app
+                .world()
+                .get_resource::<$crate::events::dialog_backend::DialogBackend>()
+                .copied()
+                .unwrap_or_default();
+            backend.add_systems::<${concat($event_type, Window)}>(app);
+
+            app
+                .add_observer(respond_to_dialog)
+                .add_systems(
+                    Update,
+                    close_dialog_when_read_only
+                        .run_if(window_active)
+                        .run_if(resource_changed::<$crate::events::mode::EventsMode>)
+                        .in_set($crate::events::sets::EventsSet::OpenDialogs)
+                );
+        }
+
+        /// Sends the input of a submitted dialog as its result, and closes the dialog either way.
+        fn respond_to_dialog(
+            trigger: On<$crate::events::dialog_backend::DialogResponse>,
+            mut commands: Commands,
+            mut result_sender: ${concat($event_type, ResultSender)},
+            query: Query<&${concat($event_type, Window)}>,
+        ) {
+            let response = trigger.event();
+            let Ok(window) = query.get(response.dialog) else {
+                return;
+            };
+
+            if response.action == $crate::events::dialog_backend::DialogAction::Submit {
+                result_sender.send(${concat($event_type, Result)} {
+                    event: window.event.clone(),
+                    result: window.input.clone(),
+                    routed: window.routed,
                 });
+            }
+            commands.entity(response.dialog).despawn();
+        }
 
-            if !open {
-                commands.entity(window.0).despawn();
+        fn window_active(
+            query: Query<Entity, With<${concat($event_type, Window)}>>,
+        ) -> bool {
+            !query.is_empty()
+        }
+
+        fn open_or_submit(
+            commands: &mut Commands,
+            prefilled: &mut $crate::events::prefill::PrefilledInputs,
+            result_sender: &mut ${concat($event_type, ResultSender)},
+            query: &Query<Entity, With<${concat($event_type, Window)}>>,
+            event: &$event_type,
+            routed: Option<bevy::ecs::event::EventId<$crate::events::events::AppEvent>>,
+        ) {
+            let kind = <$event_type as $crate::events::registry::RoutedEvent>::KIND;
+            match prefilled.take(kind) {
+                Some(input) => result_sender.send(${concat($event_type, Result)} {
+                    event: event.to_owned(),
+                    result: input,
+                    routed,
+                }),
+                None => spawn_dialog(commands, query, event, routed),
+            }
+        }
+
+        fn spawn_dialog(
+            commands: &mut Commands,
+            query: &Query<Entity, With<${concat($event_type, Window)}>>,
+            event: &$event_type,
+            routed: Option<bevy::ecs::event::EventId<$crate::events::events::AppEvent>>,
+        ) {
+            for entity in query.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.spawn((
+                ${concat($event_type, Window)} {
+                    event: event.to_owned(),
+                    input: "".to_string(),
+                    stale: false,
+                    routed,
+                },
+                $crate::events::presence::DialogOpen(
+                    <$event_type as $crate::events::registry::RoutedEvent>::KIND,
+                ),
+            ));
+        }
+
+        fn close_dialog_when_read_only(
+            mut commands: Commands,
+            mode: Res<$crate::events::mode::EventsMode>,
+            query: Query<Entity, With<${concat($event_type, Window)}>>,
+        ) {
+            if mode.allows(<$event_type as $crate::events::registry::RoutedEvent>::ACCESS) {
+                return;
+            }
+
+            for entity in query.iter() {
+                commands.entity(entity).despawn();
             }
         }
     };
@@ -175,17 +427,67 @@ macro_rules! create_event_with_dialog {
 
 #[macro_export]
 macro_rules! create_event {
+    ($plugin_name:ident, $event_type:ty, $handler_fn:ident, Observer) => {
+        use bevy::prelude::*;
+
+        $crate::create_event!(@observer_dispatcher $plugin_name, $event_type);
+        $crate::create_event!(@needs_dialog $event_type, false);
+        $crate::create_event!(@observer_handler $plugin_name, $event_type);
+
+        pub struct ${concat($plugin_name, Plugin)};
+
+        impl Plugin for ${concat($plugin_name, Plugin)} {
+            fn build(&self, app: &mut App) {
+                let handler = $crate::events::error::RegisteredHandler::register(app.world_mut(), $handler_fn);
+
+                app.insert_resource(${concat($plugin_name, Handler)}(handler))
+                    .add_observer(run_handler);
+            }
+        }
+    };
+
+    ($plugin_name:ident, $event_type:ty, $handler_fn:expr, $ui_system:expr, Observer) => {
+        use bevy::prelude::*;
+        use bevy_egui::EguiPrimaryContextPass;
+
+        $crate::create_event!(@observer_dispatcher $plugin_name, $event_type);
+        $crate::create_event!(@needs_dialog $event_type, true);
+        $crate::create_event!(@observer_handler $plugin_name, $event_type);
+
+        pub struct ${concat($plugin_name, Plugin)};
+
+        impl Plugin for ${concat($plugin_name, Plugin)} {
+            fn build(&self, app: &mut App) {
+                let handler = $crate::events::error::RegisteredHandler::register(app.world_mut(), $handler_fn);
+
+                app.insert_resource(${concat($plugin_name, Handler)}(handler))
+                    .add_observer(run_handler)
+                    .add_systems(EguiPrimaryContextPass, $ui_system);
+            }
+        }
+    };
+
     ($plugin_name:ident, $event_type:ty, $handler_fn:ident) => {
         use bevy::prelude::*;
 
+        $crate::create_event!(@buffered_dispatcher $plugin_name, $event_type);
+        $crate::create_event!(@needs_dialog $event_type, false);
+        $crate::create_event!(@buffered_handler $plugin_name, $event_type);
+
         pub struct ${concat($plugin_name, Plugin)};
 
         impl Plugin for ${concat($plugin_name, Plugin)} {
             fn build(&self, app: &mut App) {
-                app.add_event::<$event_type>().add_systems(
-                    bevy::app::Update,
-                    $handler_fn.run_if(|reader: EventReader<$event_type>| !reader.is_empty()),
-                );
+                let handler = $crate::events::error::RegisteredHandler::register(app.world_mut(), $handler_fn);
+
+                app.insert_resource(${concat($plugin_name, Handler)}(handler))
+                    .add_event::<$event_type>()
+                    .add_systems(
+                        bevy::app::Update,
+                        run_handler
+                            .run_if(|reader: EventReader<$event_type>| !reader.is_empty())
+                            .in_set($crate::events::sets::EventsSet::Handle),
+                    );
             }
         }
     };
@@ -194,65 +496,434 @@ macro_rules! create_event {
         use bevy::prelude::*;
         use bevy_egui::EguiPrimaryContextPass;
 
+        $crate::create_event!(@buffered_dispatcher $plugin_name, $event_type);
+        $crate::create_event!(@needs_dialog $event_type, true);
+        $crate::create_event!(@buffered_handler $plugin_name, $event_type);
+
         pub struct ${concat($plugin_name, Plugin)};
 
         impl Plugin for ${concat($plugin_name, Plugin)} {
             fn build(&self, app: &mut App) {
-                app.add_event::<$event_type>()
+                let handler = $crate::events::error::RegisteredHandler::register(app.world_mut(), $handler_fn);
+
+                app.insert_resource(${concat($plugin_name, Handler)}(handler))
+                    .add_event::<$event_type>()
                     .add_systems(
                         bevy::app::Update,
-                        $handler_fn.run_if(|reader: EventReader<$event_type>| !reader.is_empty()),
+                        run_handler
+                            .run_if(|reader: EventReader<$event_type>| !reader.is_empty())
+                            .in_set($crate::events::sets::EventsSet::Handle),
                     )
                     .add_systems(EguiPrimaryContextPass, $ui_system);
             }
         }
     };
+
+    (@buffered_dispatcher $plugin_name:ident, $event_type:ty) => {
+        #[derive(bevy::ecs::system::SystemParam)]
+        pub struct ${concat($plugin_name, Dispatcher)}<'w> {
+            writer: EventWriter<'w, $event_type>,
+        }
+
+        impl ${concat($plugin_name, Dispatcher)}<'_> {
+            pub fn dispatch(&mut self, event: &$event_type) {
+                self.writer.write(event.clone());
+            }
+        }
+    };
+
+    (@buffered_handler $plugin_name:ident, $event_type:ty) => {
+        $crate::create_event!(@handler $plugin_name, $event_type);
+
+        fn run_handler(
+            mut commands: Commands,
+            mut event_reader: EventReader<$event_type>,
+            mut routed_events: ResMut<$crate::events::source::RoutedEvents>,
+            handler: Res<${concat($plugin_name, Handler)}>,
+        ) {
+            for event in event_reader.read() {
+                let routed = routed_events.take(<$event_type as $crate::events::registry::RoutedEvent>::KIND);
+                queue_handler(&mut commands, &handler, event, routed);
+            }
+        }
+    };
+
+    (@observer_handler $plugin_name:ident, $event_type:ty) => {
+        $crate::create_event!(@handler $plugin_name, $event_type);
+
+        fn run_handler(
+            trigger: On<$event_type>,
+            mut commands: Commands,
+            mut routed_events: ResMut<$crate::events::source::RoutedEvents>,
+            handler: Res<${concat($plugin_name, Handler)}>,
+        ) {
+            let routed = routed_events.take(<$event_type as $crate::events::registry::RoutedEvent>::KIND);
+            queue_handler(&mut commands, &handler, trigger.event(), routed);
+        }
+    };
+
+    (@handler $plugin_name:ident, $event_type:ty) => {
+        #[derive(Resource)]
+        struct ${concat($plugin_name, Handler)}($crate::events::error::RegisteredHandler<$event_type>);
+
+        /// Runs the handler once commands apply; failures are reported by `RegisteredHandler::run`.
+        fn queue_handler(
+            commands: &mut Commands,
+            handler: &${concat($plugin_name, Handler)},
+            event: &$event_type,
+            routed: Option<bevy::ecs::event::EventId<$crate::events::events::AppEvent>>,
+        ) {
+            let handler = handler.0.clone();
+            let event = event.clone();
+            commands.queue(move |world: &mut World| {
+                let _ = handler.run(world, event.clone(), event.into(), routed);
+            });
+        }
+    };
+
+    (@needs_dialog $event_type:ty, $needs_dialog:literal) => {
+        impl $crate::events::registry::EventDialog for $event_type {
+            const NEEDS_DIALOG: bool = $needs_dialog;
+        }
+    };
+
+    (@observer_dispatcher $plugin_name:ident, $event_type:ty) => {
+        #[derive(bevy::ecs::system::SystemParam)]
+        pub struct ${concat($plugin_name, Dispatcher)}<'w, 's> {
+            commands: Commands<'w, 's>,
+        }
+
+        impl ${concat($plugin_name, Dispatcher)}<'_, '_> {
+            pub fn dispatch(&mut self, event: &$event_type) {
+                self.commands.trigger(event.clone());
+            }
+        }
+    };
 }
 
 #[macro_export]
 macro_rules! create_events_plugin {
-    ($plugin_name:ident, $($event_plugin:ident),+ $(,)?) => {
-        use bevy::prelude::*;
+    (@shortcut) => {
+        None
+    };
+
+    (@shortcut $shortcut:literal) => {
+        Some($shortcut)
+    };
 
-        #[derive(BufferedEvent)]
+    (
+        $plugin_name:ident,
+        $(
+            $event_plugin:ident: $access:ident $(($($writes:ident),+))?
+            $(if $enabled_if:path)? $(=> $shortcut:literal)?
+        ),+ $(,)?
+    ) => {
+        use bevy::prelude::*;
+        use bevy::ecs::system::SystemId;
+        use std::collections::HashMap;
+
+        /// Serialized adjacently tagged, e.g. `{"type":"ChangeCounter","data":{"counter":3}}`.
+        #[derive(BufferedEvent, Clone)]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(tag = "type", content = "data")
+        )]
         pub enum AppEvent {
             $(
                 $event_plugin(${concat($event_plugin, Event)}),
             )+
         }
 
-        pub struct $plugin_name;
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum AppEventKind {
+            $(
+                $event_plugin,
+            )+
+        }
+
+        impl AppEventKind {
+            pub const ALL: &'static [AppEventKind] = &[$(AppEventKind::$event_plugin,)+];
+
+            pub fn info(&self) -> $crate::events::registry::EventInfo {
+                match self {
+                    $(
+                        AppEventKind::$event_plugin => $crate::events::registry::EventInfo {
+                            name: stringify!($event_plugin),
+                            name_id: concat!("event-", stringify!($event_plugin)),
+                            description_id: concat!("event-", stringify!($event_plugin), "-description"),
+                            shortcut: $crate::create_events_plugin!(@shortcut $($shortcut)?),
+                            access: <${concat($event_plugin, Event)} as $crate::events::registry::RoutedEvent>::ACCESS,
+                            writes: &[$($($crate::person_resource::PersonField::$writes),+)?],
+                            needs_dialog: <${concat($event_plugin, Event)} as $crate::events::registry::EventDialog>::NEEDS_DIALOG,
+                        },
+                    )+
+                }
+            }
+
+            /// Builds the event from the current person, for callers without a button context.
+            pub fn construct(&self, person: &$crate::person_resource::PersonResource) -> AppEvent {
+                match self {
+                    $(
+                        AppEventKind::$event_plugin => AppEvent::$event_plugin(person.into()),
+                    )+
+                }
+            }
+        }
+
+        impl std::str::FromStr for AppEventKind {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(
+                        stringify!($event_plugin) => Ok(AppEventKind::$event_plugin),
+                    )+
+                    _ => Err(format!("Unknown event `{s}`")),
+                }
+            }
+        }
+
+        impl AppEvent {
+            pub fn kind(&self) -> AppEventKind {
+                match self {
+                    $(
+                        AppEvent::$event_plugin(_) => AppEventKind::$event_plugin,
+                    )+
+                }
+            }
+
+            pub fn access(&self) -> $crate::events::registry::EventAccess {
+                match self {
+                    $(
+                        AppEvent::$event_plugin(_) => {
+                            <${concat($event_plugin, Event)} as $crate::events::registry::RoutedEvent>::ACCESS
+                        }
+                    )+
+                }
+            }
+        }
+
+        $(
+            impl From<${concat($event_plugin, Event)}> for AppEvent {
+                fn from(event: ${concat($event_plugin, Event)}) -> Self {
+                    AppEvent::$event_plugin(event)
+                }
+            }
+
+            impl $crate::events::registry::RoutedEvent for ${concat($event_plugin, Event)} {
+                const KIND: AppEventKind = AppEventKind::$event_plugin;
+                const ACCESS: $crate::events::registry::EventAccess =
+                    $crate::events::registry::EventAccess::$access;
+            }
+        )+
+
+        /// Emitted by the router for every `AppEvent` it dispatches.
+        #[derive(BufferedEvent, Clone)]
+        pub struct EventRouted {
+            pub event: AppEvent,
+            /// Carried to the handler and its dialog, and from there to `PersonChanged::routed`.
+            pub id: bevy::ecs::event::EventId<AppEvent>,
+            pub source: $crate::events::source::EventSource,
+            /// The edit transaction that was open when the event was routed.
+            pub transaction: Option<$crate::events::transaction::TransactionId>,
+        }
+
+        /// Emitted by the router when an `AppEvent` is dropped instead of dispatched.
+        #[derive(BufferedEvent, Clone)]
+        pub struct EventRejected {
+            pub event: AppEvent,
+            pub reason: $crate::localization::LocalizedText,
+        }
+
+        /// `EventsMode` and the result of every `enabled_if` predicate, refreshed each frame before routing.
+        #[derive(Resource, Default)]
+        pub struct EventAvailability {
+            mode: $crate::events::mode::EventsMode,
+            disabled: HashMap<AppEventKind, $crate::localization::LocalizedText>,
+        }
+
+        impl EventAvailability {
+            pub fn check(&self, event: &AppEvent) -> Result<(), $crate::localization::LocalizedText> {
+                if !self.mode.allows(event.access()) {
+                    return Err($crate::localization::LocalizedText::new("event-read-only"));
+                }
+
+                match self.disabled.get(&event.kind()) {
+                    Some(reason) => Err(reason.clone()),
+                    None => Ok(()),
+                }
+            }
+        }
+
+        #[derive(Resource)]
+        struct EventConditions(
+            Vec<(AppEventKind, SystemId<(), Result<(), $crate::localization::LocalizedText>>)>,
+        );
+
+        /// Routes `AppEvent`s to the plugins of every registered event.
+        #[derive(Default)]
+        pub struct $plugin_name {
+            /// Draws the dialogs generated by `create_event_with_dialog!`.
+            pub dialogs: $crate::events::dialog_backend::DialogBackend,
+        }
 
         impl Plugin for $plugin_name {
             fn build(&self, app: &mut App) {
+                let mut conditions = Vec::new();
+                $(
+                    $(
+                        conditions.push((
+                            AppEventKind::$event_plugin,
+                            app.world_mut().register_system($enabled_if),
+                        ));
+                    )?
+                )+
+
+                // Left alone when the app inserted its own limits before adding the plugin.
+                if !app.world().contains_resource::<$crate::events::limits::Limits>() {
+                    app.insert_resource($crate::events::limits::Limits::from_config());
+                }
+
                 app
                     .add_event::<AppEvent>()
-                    .add_systems(Update, on_track_event.run_if(has_track_events))
-                    .add_plugins(($(${concat($event_plugin, Plugin)},)+));
+                    .add_event::<EventRouted>()
+                    .add_event::<EventRejected>()
+                    .add_event::<$crate::events::prefill::DialogSubmitted>()
+                    .add_event::<$crate::notify::Notify>()
+                    .add_event::<$crate::events::error::EventFailed>()
+                    .init_resource::<$crate::events::prefill::PrefilledInputs>()
+                    .init_resource::<$crate::events::mode::EventsMode>()
+                    .init_resource::<EventAvailability>()
+                    .init_resource::<$crate::events::presence::EditorPresence>()
+                    .init_resource::<$crate::events::source::EventSources>()
+                    .init_resource::<$crate::events::source::RoutedEvents>()
+                    .init_resource::<$crate::events::stale::StalePolicy>()
+                    .insert_resource(EventConditions(conditions))
+                    .configure_sets(
+                        Update,
+                        (
+                            $crate::events::sets::EventsSet::Route,
+                            $crate::events::sets::EventsSet::OpenDialogs,
+                            $crate::events::sets::EventsSet::Handle,
+                            $crate::events::sets::EventsSet::Commit,
+                            $crate::events::sets::EventsSet::Post,
+                        )
+                            .chain(),
+                    )
+                    .add_systems(
+                        Update,
+                        (update_event_availability, on_track_event.run_if(has_track_events))
+                            .chain()
+                            .in_set($crate::events::sets::EventsSet::Route),
+                    )
+                    .add_systems(
+                        Update,
+                        log_rejected_events
+                            .run_if(has_rejected_events)
+                            .in_set($crate::events::sets::EventsSet::Post),
+                    )
+                    .init_resource::<$crate::events::dialog_definition::DialogDefinitions>()
+                    .init_resource::<$crate::events::dialog_backend::DialogWantsInput>()
+                    .insert_resource(self.dialogs)
+                    .add_plugins($crate::events::transaction::TransactionPlugin);
+
+                if self.dialogs == $crate::events::dialog_backend::DialogBackend::BevyUi {
+                    $crate::events::bevy_ui_dialogs::add_widget_plugins(app);
+                }
+
+                // Dialog definitions are assets, loaded by the event plugins below.
+                if app.world().contains_resource::<AssetServer>() {
+                    app.add_plugins($crate::events::dialog_definition::DialogDefinitionPlugin);
+                }
+
+                app.add_plugins(($(${concat($event_plugin, Plugin)},)+));
             }
         }
 
+        fn update_event_availability(world: &mut World) {
+            let conditions = world.resource::<EventConditions>().0.clone();
+
+            let mut disabled = HashMap::new();
+            for (kind, condition) in conditions {
+                match world.run_system(condition) {
+                    Ok(Ok(())) => {}
+                    Ok(Err(reason)) => {
+                        disabled.insert(kind, reason);
+                    }
+                    Err(error) => {
+                        disabled.insert(
+                            kind,
+                            $crate::localization::LocalizedText::new("event-condition-failed")
+                                .with_arg("error", error.to_string()),
+                        );
+                    }
+                }
+            }
+
+            let mode = *world.resource::<$crate::events::mode::EventsMode>();
+            let mut availability = world.resource_mut::<EventAvailability>();
+            availability.mode = mode;
+            availability.disabled = disabled;
+        }
+
         fn has_track_events(
             event_reader: EventReader<AppEvent>
         ) -> bool {
             !event_reader.is_empty()
         }
 
-        #[allow(non_snake_case)]
+        fn has_rejected_events(
+            event_reader: EventReader<EventRejected>
+        ) -> bool {
+            !event_reader.is_empty()
+        }
+
+        #[allow(non_snake_case, clippy::too_many_arguments)]
         fn on_track_event(
             mut event_reader: EventReader<AppEvent>,
+            mut routed_writer: EventWriter<EventRouted>,
+            mut rejected_writer: EventWriter<EventRejected>,
+            mut sources: ResMut<$crate::events::source::EventSources>,
+            mut routed_events: ResMut<$crate::events::source::RoutedEvents>,
+            availability: Res<EventAvailability>,
+            transaction: Res<$crate::events::transaction::Transaction>,
             $(
-                mut ${concat($event_plugin, Writer)}: EventWriter<${concat($event_plugin, Event)}>,
+                mut ${concat($event_plugin, Writer)}: ${concat($event_plugin, Dispatcher)},
             )+
         ) {
-            for event in event_reader.read() {
+            for (event, id) in event_reader.read_with_id() {
+                let source = sources.take(id);
+                if let Err(reason) = availability.check(event) {
+                    rejected_writer.write(EventRejected {
+                        event: event.clone(),
+                        reason,
+                    });
+                    continue;
+                }
+
+                routed_events.push(event.kind(), id);
                 match event {
                     $(
                         AppEvent::$event_plugin(e) => {
-                            ${concat($event_plugin, Writer)}.write(e.clone());
+                            ${concat($event_plugin, Writer)}.dispatch(e);
                         }
                     )+
                 }
+
+                routed_writer.write(EventRouted {
+                    event: event.clone(),
+                    id,
+                    source,
+                    transaction: transaction.id(),
+                });
+            }
+        }
+
+        fn log_rejected_events(mut event_reader: EventReader<EventRejected>) {
+            for rejected in event_reader.read() {
+                warn!("Rejected {:?}: {}", rejected.event.kind(), rejected.reason);
             }
         }
     };
@@ -1,14 +1,45 @@
 use bevy::ecs::event::BufferedEvent;
 
-use crate::{create_event, person_resource::PersonResource};
+use crate::{
+    create_event,
+    events::{
+        error::HandlerError,
+        limits::{Limits, OverflowPolicy},
+        person_changed::{PersonEditor, PersonView},
+    },
+    localization::LocalizedText,
+    person_resource::PersonResource,
+};
 
 #[derive(BufferedEvent, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GrowOlderEvent;
 
+impl From<&PersonResource> for GrowOlderEvent {
+    fn from(_: &PersonResource) -> Self {
+        GrowOlderEvent
+    }
+}
+
 create_event!(GrowOlder, GrowOlderEvent, grow_older);
 
-fn grow_older(mut person: ResMut<PersonResource>, mut event_reader: EventReader<GrowOlderEvent>) {
-    for _ in event_reader.read() {
-        person.age += 1;
+fn grow_older(
+    In(event): In<GrowOlderEvent>,
+    mut person: PersonEditor,
+    limits: Res<Limits>,
+) -> Result<(), HandlerError> {
+    let age = limits.age.add(person.age, 1)?;
+    person.edit(event, |person| {
+        person.age = age;
+    });
+    Ok(())
+}
+
+/// Only `OverflowPolicy::Reject` disables the event, the other policies keep the age in bounds.
+pub fn can_grow_older(person: PersonView, limits: Res<Limits>) -> Result<(), LocalizedText> {
+    if limits.age.policy == OverflowPolicy::Reject && person.age >= limits.age.max {
+        return Err(LocalizedText::new("event-too-old").with_arg("max", limits.age.max));
     }
+
+    Ok(())
 }
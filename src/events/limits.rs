@@ -0,0 +1,292 @@
+use std::{fmt, io, str::FromStr};
+
+use bevy::{asset::io::file::FileAssetReader, prelude::*};
+
+/// Overrides for the default `Limits`.
+///
+/// One setting per line, `<field>.<min|max|policy> = <value>`, e.g. `counter.policy = saturate`.
+/// Lines starting with `#` are ignored. Relative to the asset folder.
+pub const LIMITS_PATH: &str = "limits.cfg";
+
+/// What happens when arithmetic on a numeric field leaves its bounds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverflowPolicy {
+    /// Fail the handler with `OutOfBounds`.
+    Reject,
+    /// Clamp to the nearest bound.
+    Saturate,
+    /// Continue from the other bound.
+    Wrap,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(OverflowPolicy::Reject),
+            "saturate" => Ok(OverflowPolicy::Saturate),
+            "wrap" => Ok(OverflowPolicy::Wrap),
+            _ => Err(format!("Unknown overflow policy `{s}`")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutOfBounds {
+    pub value: i64,
+    pub min: u32,
+    pub max: u32,
+}
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is outside {}..={}", self.value, self.min, self.max)
+    }
+}
+
+impl std::error::Error for OutOfBounds {}
+
+/// Bounds and overflow policy of one numeric field.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FieldLimits {
+    pub min: u32,
+    pub max: u32,
+    pub policy: OverflowPolicy,
+}
+
+impl FieldLimits {
+    pub fn contains(&self, value: u32) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+
+    /// `value + delta`, brought back within bounds according to the policy.
+    pub fn add(&self, value: u32, delta: i64) -> Result<u32, OutOfBounds> {
+        self.fit((value as i64).saturating_add(delta))
+    }
+
+    fn fit(&self, value: i64) -> Result<u32, OutOfBounds> {
+        let (min, max) = (self.min as i64, self.max as i64);
+        if (min..=max).contains(&value) {
+            return Ok(value as u32);
+        }
+
+        match self.policy {
+            OverflowPolicy::Reject => Err(OutOfBounds {
+                value,
+                min: self.min,
+                max: self.max,
+            }),
+            OverflowPolicy::Saturate => Ok(value.clamp(min, max) as u32),
+            OverflowPolicy::Wrap => Ok((min + (value - min).rem_euclid(max - min + 1)) as u32),
+        }
+    }
+}
+
+/// Limits of the numeric `PersonResource` fields, built from defaults and `LIMITS_PATH`.
+#[derive(Resource, Clone, Debug)]
+pub struct Limits {
+    pub age: FieldLimits,
+    pub counter: FieldLimits,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            age: FieldLimits {
+                min: 0,
+                max: 150,
+                policy: OverflowPolicy::Reject,
+            },
+            counter: FieldLimits {
+                min: 0,
+                max: u32::MAX,
+                policy: OverflowPolicy::Reject,
+            },
+        }
+    }
+}
+
+impl Limits {
+    /// Defaults with the overrides from `LIMITS_PATH`, if it exists.
+    pub fn from_config() -> Self {
+        let mut limits = Limits::default();
+        let path = FileAssetReader::new("assets").root_path().join(LIMITS_PATH);
+        match std::fs::read_to_string(&path) {
+            Ok(source) => limits.load_overrides(&source),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                info!("No limits at {}, using the defaults", path.display());
+            }
+            Err(error) => warn!("Could not read limits {}: {error}", path.display()),
+        }
+        limits
+    }
+
+    pub fn load_overrides(&mut self, source: &str) {
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Err(error) = self.apply_line(line) {
+                warn!("Invalid limits line `{line}`: {error}");
+            }
+        }
+
+        for (name, field) in [("age", &mut self.age), ("counter", &mut self.counter)] {
+            if field.min > field.max {
+                warn!("Limits for {name} have min above max, swapping them");
+                std::mem::swap(&mut field.min, &mut field.max);
+            }
+        }
+    }
+
+    fn apply_line(&mut self, line: &str) -> Result<(), String> {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or("expected `<field>.<key> = <value>`")?;
+        let (field, setting) = key
+            .trim()
+            .split_once('.')
+            .ok_or("expected `<field>.<key>`")?;
+        let field = match field {
+            "age" => &mut self.age,
+            "counter" => &mut self.counter,
+            _ => return Err(format!("unknown field `{field}`")),
+        };
+
+        let value = value.trim();
+        let parse_bound = |value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid bound `{value}`"))
+        };
+        match setting {
+            "min" => field.min = parse_bound(value)?,
+            "max" => field.max = parse_bound(value)?,
+            "policy" => field.policy = value.parse()?,
+            _ => return Err(format!("unknown setting `{setting}`")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full(policy: OverflowPolicy) -> FieldLimits {
+        FieldLimits {
+            min: 0,
+            max: u32::MAX,
+            policy,
+        }
+    }
+
+    #[test]
+    fn values_within_bounds_pass_under_every_policy() {
+        for policy in [
+            OverflowPolicy::Reject,
+            OverflowPolicy::Saturate,
+            OverflowPolicy::Wrap,
+        ] {
+            let limits = full(policy);
+            assert_eq!(limits.add(u32::MAX, 0), Ok(u32::MAX));
+            assert_eq!(limits.add(u32::MAX, -1), Ok(u32::MAX - 1));
+            assert_eq!(limits.add(0, 0), Ok(0));
+            assert_eq!(limits.add(0, 1), Ok(1));
+        }
+    }
+
+    #[test]
+    fn reject_fails_past_either_bound() {
+        let limits = full(OverflowPolicy::Reject);
+        assert_eq!(
+            limits.add(u32::MAX, 1),
+            Err(OutOfBounds {
+                value: u32::MAX as i64 + 1,
+                min: 0,
+                max: u32::MAX,
+            })
+        );
+        assert_eq!(
+            limits.add(0, -1),
+            Err(OutOfBounds {
+                value: -1,
+                min: 0,
+                max: u32::MAX,
+            })
+        );
+    }
+
+    #[test]
+    fn saturate_clamps_to_the_nearest_bound() {
+        let limits = full(OverflowPolicy::Saturate);
+        assert_eq!(limits.add(u32::MAX, 1), Ok(u32::MAX));
+        assert_eq!(limits.add(u32::MAX, i64::MAX), Ok(u32::MAX));
+        assert_eq!(limits.add(0, -1), Ok(0));
+        assert_eq!(limits.add(0, i64::MIN), Ok(0));
+    }
+
+    #[test]
+    fn wrap_continues_from_the_other_bound() {
+        let limits = full(OverflowPolicy::Wrap);
+        assert_eq!(limits.add(u32::MAX, 1), Ok(0));
+        assert_eq!(limits.add(u32::MAX, 3), Ok(2));
+        assert_eq!(limits.add(0, -1), Ok(u32::MAX));
+        assert_eq!(limits.add(0, -3), Ok(u32::MAX - 2));
+
+        let limits = FieldLimits {
+            min: 10,
+            max: 20,
+            policy: OverflowPolicy::Wrap,
+        };
+        assert_eq!(limits.add(20, 1), Ok(10));
+        assert_eq!(limits.add(10, -1), Ok(20));
+        // More than one full turn in either direction.
+        assert_eq!(limits.add(20, 23), Ok(10));
+        assert_eq!(limits.add(15, 11 * 3), Ok(15));
+        assert_eq!(limits.add(15, -11 * 3 - 1), Ok(14));
+    }
+
+    #[test]
+    fn overrides_replace_defaults() {
+        let mut limits = Limits::default();
+        limits.load_overrides(
+            "# comment\n\n  age.max = 120  \ncounter.min=5\ncounter.policy = wrap\n",
+        );
+        assert_eq!(limits.age.max, 120);
+        assert_eq!(limits.counter.min, 5);
+        assert_eq!(limits.counter.policy, OverflowPolicy::Wrap);
+    }
+
+    #[test]
+    fn min_above_max_is_swapped() {
+        let mut limits = Limits::default();
+        limits.load_overrides("age.min = 200\nage.max = 50");
+        assert_eq!((limits.age.min, limits.age.max), (50, 200));
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let mut limits = Limits::default();
+        limits.load_overrides(
+            "age.max\n\
+             age = 3\n\
+             height.max = 3\n\
+             age.step = 3\n\
+             age.max = -3\n\
+             age.max = many\n\
+             counter.policy = bounce\n\
+             age.min = 7",
+        );
+        assert_eq!(
+            limits.age,
+            FieldLimits {
+                min: 7,
+                ..Limits::default().age
+            }
+        );
+        assert_eq!(limits.counter, Limits::default().counter);
+    }
+}
@@ -1,7 +1,24 @@
+pub mod bevy_ui_dialogs;
 pub mod change_color;
 pub mod change_counter;
 pub mod change_location;
 pub mod change_name;
+pub mod dialog_backend;
+pub mod dialog_definition;
+pub mod egui_dialogs;
+#[cfg(feature = "serde")]
+pub mod envelope;
+pub mod error;
 pub mod events;
 pub mod events_macro;
 pub mod grow_older;
+pub mod limits;
+pub mod mode;
+pub mod person_changed;
+pub mod prefill;
+pub mod presence;
+pub mod registry;
+pub mod sets;
+pub mod source;
+pub mod stale;
+pub mod transaction;
@@ -0,0 +1,22 @@
+use bevy::prelude::Resource;
+
+use crate::events::registry::EventAccess;
+
+/// Global switch for presentation/kiosk mode.
+///
+/// In `ReadOnly` mode the router rejects mutating events and their dialogs are closed.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventsMode {
+    #[default]
+    Editable,
+    ReadOnly,
+}
+
+impl EventsMode {
+    pub fn allows(&self, access: EventAccess) -> bool {
+        match self {
+            EventsMode::Editable => true,
+            EventsMode::ReadOnly => access == EventAccess::NonMutating,
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::ops::Deref;
+
+use bevy::{
+    ecs::{event::EventId, system::SystemParam},
+    prelude::*,
+};
+
+use crate::{
+    events::{
+        events::AppEvent,
+        sets::EventsSet,
+        source::RoutedEvents,
+        transaction::{Transaction, TransactionId},
+    },
+    person_resource::{PersonField, PersonResource, PersonValue},
+};
+
+/// Where a change to `PersonResource` was made.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ChangeOrigin {
+    Local,
+    /// Replicated from another instance through the relay.
+    Remote {
+        user: String,
+    },
+}
+
+/// Emitted once per field whenever a handler changes `PersonResource` through `PersonEditor`.
+#[derive(BufferedEvent, Clone)]
+pub struct PersonChanged {
+    pub field: PersonField,
+    pub old: PersonValue,
+    pub new: PersonValue,
+    pub cause: AppEvent,
+    /// The id on `EventRouted` of the event whose handler or dialog made the change. `None` for
+    /// remote changes and committed transactions.
+    pub routed: Option<EventId<AppEvent>>,
+    pub origin: ChangeOrigin,
+    /// Set for the changes of a committed edit transaction.
+    pub transaction: Option<TransactionId>,
+}
+
+/// Write access to `PersonResource` that announces every changed field as `PersonChanged`.
+///
+/// While an edit transaction is open, local edits go to its staging copy instead, which is also
+/// what the editor derefs to.
+#[derive(SystemParam)]
+pub struct PersonEditor<'w> {
+    person: ResMut<'w, PersonResource>,
+    transaction: ResMut<'w, Transaction>,
+    changed_writer: EventWriter<'w, PersonChanged>,
+    routed_events: Option<Res<'w, RoutedEvents>>,
+}
+
+impl PersonEditor<'_> {
+    pub fn edit(&mut self, cause: impl Into<AppEvent>, edit: impl FnOnce(&mut PersonResource)) {
+        self.edit_from(ChangeOrigin::Local, cause, edit);
+    }
+
+    pub fn edit_from(
+        &mut self,
+        origin: ChangeOrigin,
+        cause: impl Into<AppEvent>,
+        edit: impl FnOnce(&mut PersonResource),
+    ) {
+        let routed = match origin {
+            ChangeOrigin::Local => self.routed(),
+            ChangeOrigin::Remote { .. } => None,
+        };
+        self.apply(origin, routed, cause, edit);
+    }
+
+    /// Like `edit`, for a dialog that applies its result after the handler of `routed` returned.
+    pub fn edit_routed(
+        &mut self,
+        routed: Option<EventId<AppEvent>>,
+        cause: impl Into<AppEvent>,
+        edit: impl FnOnce(&mut PersonResource),
+    ) {
+        self.apply(ChangeOrigin::Local, routed, cause, edit);
+    }
+
+    /// The routed event whose handler is running.
+    pub fn routed(&self) -> Option<EventId<AppEvent>> {
+        self.routed_events
+            .as_ref()
+            .and_then(|routed_events| routed_events.handling())
+    }
+
+    fn apply(
+        &mut self,
+        origin: ChangeOrigin,
+        routed: Option<EventId<AppEvent>>,
+        cause: impl Into<AppEvent>,
+        edit: impl FnOnce(&mut PersonResource),
+    ) {
+        if origin == ChangeOrigin::Local
+            && let Some(staged) = &mut self.transaction.staged
+        {
+            let mut person = staged.person.clone();
+            edit(&mut person);
+
+            let cause = cause.into();
+            for field in staged.person.diff(&person) {
+                staged.causes.insert(field, cause.clone());
+            }
+            person.revision += 1;
+            staged.person = person;
+            return;
+        }
+
+        let mut person = self.person.clone();
+        edit(&mut person);
+
+        let changed = self.person.diff(&person);
+        if changed.is_empty() {
+            return;
+        }
+
+        // Remote changes reach the staging copy too, unless the transaction changed the field.
+        if let Some(staged) = &mut self.transaction.staged {
+            for field in &changed {
+                staged.base.set(*field, person.get(*field));
+                if !staged.causes.contains_key(field) {
+                    staged.person.set(*field, person.get(*field));
+                }
+            }
+            staged.person.revision += 1;
+        }
+
+        let cause = cause.into();
+        for field in changed {
+            self.changed_writer.write(PersonChanged {
+                field,
+                old: self.person.get(field),
+                new: person.get(field),
+                cause: cause.clone(),
+                routed,
+                origin: origin.clone(),
+                transaction: None,
+            });
+        }
+
+        person.revision += 1;
+        *self.person = person;
+    }
+
+    pub fn transaction(&self) -> Option<TransactionId> {
+        self.transaction.id()
+    }
+
+    /// Opens an edit transaction, returns `false` if one is already open.
+    pub fn begin(&mut self) -> bool {
+        self.transaction.open(&self.person)
+    }
+
+    /// Applies the fields changed by the open transaction to the current person if `validate`
+    /// accepts the result. The transaction is closed either way.
+    pub fn commit(
+        &mut self,
+        validate: impl FnOnce(&PersonResource) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let Some(mut staged) = self.transaction.staged.take() else {
+            return Err("No edit transaction is open".to_string());
+        };
+
+        let mut person = self.person.clone();
+        for field in staged.base.diff(&staged.person) {
+            person.set(field, staged.person.get(field));
+        }
+        // Events stamped inside the transaction must not match a later revision.
+        person.revision = self.person.revision.max(staged.person.revision) + 1;
+
+        if let Err(reason) = validate(&person) {
+            self.person.revision = person.revision;
+            return Err(reason);
+        }
+
+        for field in self.person.diff(&person) {
+            let Some(cause) = staged.causes.remove(&field) else {
+                continue;
+            };
+            self.changed_writer.write(PersonChanged {
+                field,
+                old: self.person.get(field),
+                new: person.get(field),
+                cause,
+                routed: None,
+                origin: ChangeOrigin::Local,
+                transaction: Some(staged.id),
+            });
+        }
+
+        *self.person = person;
+        Ok(())
+    }
+
+    /// Discards the open transaction.
+    pub fn abort(&mut self) {
+        if let Some(staged) = self.transaction.staged.take() {
+            self.person.revision = self.person.revision.max(staged.person.revision) + 1;
+        }
+    }
+}
+
+impl Deref for PersonEditor<'_> {
+    type Target = PersonResource;
+
+    fn deref(&self) -> &Self::Target {
+        self.transaction.staged().unwrap_or(&self.person)
+    }
+}
+
+/// Read access to `PersonResource` that sees the staged copy while an edit transaction is open.
+///
+/// Use it wherever events snapshot the person, so they match what handlers will edit.
+#[derive(SystemParam)]
+pub struct PersonView<'w> {
+    person: Res<'w, PersonResource>,
+    transaction: Res<'w, Transaction>,
+}
+
+impl PersonView<'_> {
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
+}
+
+impl Deref for PersonView<'_> {
+    type Target = PersonResource;
+
+    fn deref(&self) -> &Self::Target {
+        self.transaction.staged().unwrap_or(&self.person)
+    }
+}
+
+pub struct PersonChangedPlugin;
+
+impl Plugin for PersonChangedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PersonChanged>().add_systems(
+            Update,
+            log_person_changes
+                .run_if(|reader: EventReader<PersonChanged>| !reader.is_empty())
+                .in_set(EventsSet::Post),
+        );
+    }
+}
+
+fn log_person_changes(mut event_reader: EventReader<PersonChanged>) {
+    for change in event_reader.read() {
+        debug!(
+            "{:?} changed by {:?}: {:?} -> {:?}",
+            change.field,
+            change.cause.kind(),
+            change.old,
+            change.new
+        );
+    }
+}
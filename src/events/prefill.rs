@@ -0,0 +1,31 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::events::events::AppEventKind;
+
+/// Dialog results supplied ahead of time, e.g. by a script.
+///
+/// When a dialog event is dispatched and an input is queued for its kind, the dialog is skipped
+/// and the input is submitted as its result.
+#[derive(Resource, Default)]
+pub struct PrefilledInputs {
+    pending: HashMap<AppEventKind, VecDeque<String>>,
+}
+
+impl PrefilledInputs {
+    pub fn push(&mut self, kind: AppEventKind, input: String) {
+        self.pending.entry(kind).or_default().push_back(input);
+    }
+
+    pub fn take(&mut self, kind: AppEventKind) -> Option<String> {
+        self.pending.get_mut(&kind)?.pop_front()
+    }
+}
+
+/// Emitted whenever a dialog result is submitted, either by the user or from `PrefilledInputs`.
+#[derive(BufferedEvent, Clone)]
+pub struct DialogSubmitted {
+    pub kind: AppEventKind,
+    pub input: String,
+}
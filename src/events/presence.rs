@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{events::events::AppEventKind, localization::LocalizedText};
+
+/// Marks an open dialog window with the event it belongs to.
+#[derive(Component)]
+pub struct DialogOpen(pub AppEventKind);
+
+/// Other users and the dialogs they have open, kept up to date by `ReplicationPlugin`.
+#[derive(Resource, Default)]
+pub struct EditorPresence {
+    editing: HashMap<String, Vec<AppEventKind>>,
+}
+
+impl EditorPresence {
+    pub fn set(&mut self, user: String, kinds: Vec<AppEventKind>) {
+        if kinds.is_empty() {
+            self.editing.remove(&user);
+        } else {
+            self.editing.insert(user, kinds);
+        }
+    }
+
+    pub fn remove(&mut self, user: &str) {
+        self.editing.remove(user);
+    }
+
+    pub fn editors(&self, kind: AppEventKind) -> Vec<&str> {
+        let mut editors: Vec<&str> = self
+            .editing
+            .iter()
+            .filter(|(_, kinds)| kinds.contains(&kind))
+            .map(|(user, _)| user.as_str())
+            .collect();
+        editors.sort();
+        editors
+    }
+
+    /// "Also editing: …" line for a dialog header, if anyone else has the same dialog open.
+    pub fn header(&self, kind: AppEventKind) -> Option<LocalizedText> {
+        let editors = self.editors(kind);
+        if editors.is_empty() {
+            return None;
+        }
+        Some(LocalizedText::new("dialog-presence").with_arg("users", editors.join(", ")))
+    }
+}
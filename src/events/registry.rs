@@ -0,0 +1,36 @@
+use crate::{events::events::AppEventKind, person_resource::PersonField};
+
+/// Whether an event changes the person when it is applied.
+///
+/// Declared per event in `create_events_plugin!`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventAccess {
+    Mutating,
+    NonMutating,
+}
+
+/// Implemented by `create_events_plugin!` for every event it routes.
+pub trait RoutedEvent {
+    const KIND: AppEventKind;
+    const ACCESS: EventAccess;
+}
+
+/// Implemented by the event macros: whether dispatching the event opens a dialog.
+pub trait EventDialog {
+    const NEEDS_DIALOG: bool;
+}
+
+/// Static metadata of a registered event, generated by `create_events_plugin!`.
+///
+/// `name_id` and `description_id` are localization message ids.
+#[derive(Clone, Copy, Debug)]
+pub struct EventInfo {
+    pub name: &'static str,
+    pub name_id: &'static str,
+    pub description_id: &'static str,
+    pub shortcut: Option<&'static str>,
+    pub access: EventAccess,
+    /// Fields the event may change, declared after its access.
+    pub writes: &'static [PersonField],
+    pub needs_dialog: bool,
+}
@@ -0,0 +1,18 @@
+use bevy::prelude::SystemSet;
+
+/// Ordering of the buffered event pipeline in `Update`, chained by `EventsPlugin`.
+///
+/// User systems that need every person change of the frame applied can run in `Post`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EventsSet {
+    /// `AppEvent` routing into typed events.
+    Route,
+    /// Dialog windows spawned or closed for typed events.
+    OpenDialogs,
+    /// Event handlers and dialog result handlers.
+    Handle,
+    /// Edit transactions committed or aborted, after the handlers that staged their changes.
+    Commit,
+    /// Diagnostics and reactions to applied changes.
+    Post,
+}
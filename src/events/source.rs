@@ -0,0 +1,105 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use bevy::{ecs::event::EventId, ecs::system::SystemParam, prelude::*};
+
+use crate::events::events::{AppEvent, AppEventKind};
+
+/// Where an `AppEvent` or a change to the person came from.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventSource {
+    Button,
+    Shortcut,
+    Palette,
+    Script,
+    /// An action button on a toast.
+    Toast,
+    /// Fired by the `Scheduler`.
+    Schedule,
+    /// Sent by a client of the IPC control socket.
+    Ipc,
+    /// Dispatched by a rule of the `RuleEngine`.
+    Rule {
+        name: String,
+    },
+    /// Replicated from another instance through the relay.
+    Network {
+        user: String,
+    },
+    #[default]
+    Other,
+}
+
+impl fmt::Display for EventSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventSource::Button => write!(f, "button"),
+            EventSource::Shortcut => write!(f, "shortcut"),
+            EventSource::Palette => write!(f, "palette"),
+            EventSource::Script => write!(f, "script"),
+            EventSource::Toast => write!(f, "toast"),
+            EventSource::Schedule => write!(f, "schedule"),
+            EventSource::Ipc => write!(f, "ipc"),
+            EventSource::Rule { name } => write!(f, "rule ({name})"),
+            EventSource::Network { user } => write!(f, "network ({user})"),
+            EventSource::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Sources of `AppEvent`s written through `AppEventWriter` that the router has not read yet.
+#[derive(Resource, Default)]
+pub struct EventSources(HashMap<EventId<AppEvent>, EventSource>);
+
+impl EventSources {
+    pub fn take(&mut self, id: EventId<AppEvent>) -> EventSource {
+        self.0.remove(&id).unwrap_or_default()
+    }
+}
+
+/// Ids of routed events, as reported on `EventRouted`, that their handler or dialog has not picked
+/// up yet, and the id of the event whose handler is running.
+///
+/// `PersonEditor` stamps every change with the latter, so the change can be traced back to the
+/// routed event even when its dialog result arrives frames later.
+#[derive(Resource, Default)]
+pub struct RoutedEvents {
+    pending: HashMap<AppEventKind, VecDeque<EventId<AppEvent>>>,
+    handling: Option<EventId<AppEvent>>,
+}
+
+impl RoutedEvents {
+    pub(crate) fn push(&mut self, kind: AppEventKind, id: EventId<AppEvent>) {
+        self.pending.entry(kind).or_default().push_back(id);
+    }
+
+    /// The oldest dispatched event of `kind`, events of one kind reach their handler in order.
+    pub fn take(&mut self, kind: AppEventKind) -> Option<EventId<AppEvent>> {
+        self.pending.get_mut(&kind)?.pop_front()
+    }
+
+    pub fn handling(&self) -> Option<EventId<AppEvent>> {
+        self.handling
+    }
+
+    pub(crate) fn set_handling(&mut self, id: Option<EventId<AppEvent>>) {
+        self.handling = id;
+    }
+}
+
+/// `EventWriter<AppEvent>` that remembers where each event came from, reported on `EventRouted`.
+#[derive(SystemParam)]
+pub struct AppEventWriter<'w> {
+    writer: EventWriter<'w, AppEvent>,
+    sources: ResMut<'w, EventSources>,
+}
+
+impl AppEventWriter<'_> {
+    pub fn write(&mut self, event: AppEvent, source: EventSource) {
+        let id = self.writer.write(event);
+        self.sources.0.insert(id, source);
+    }
+}
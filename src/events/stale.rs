@@ -0,0 +1,60 @@
+use std::fmt;
+
+use bevy::prelude::Resource;
+
+/// What handlers do with an event stamped with an older `PersonResource::revision`.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StalePolicy {
+    /// Apply the event to the current state instead of its snapshot.
+    Rebase,
+    /// Fail the handler with `StaleEvent`.
+    Reject,
+    /// Put the result back in front of the user against the current state.
+    #[default]
+    Prompt,
+}
+
+/// Outcome of `StalePolicy::check` for an event that may be applied.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Staleness {
+    Current,
+    /// The snapshot in the event is outdated, use the current state.
+    Rebased,
+}
+
+/// The person changed between creating an event and handling it.
+#[derive(Clone, Debug)]
+pub struct StaleEvent {
+    pub revision: u64,
+    pub current: u64,
+    pub policy: StalePolicy,
+}
+
+impl fmt::Display for StaleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "changed since the event was created (revision {}, now {})",
+            self.revision, self.current
+        )
+    }
+}
+
+impl std::error::Error for StaleEvent {}
+
+impl StalePolicy {
+    /// Compares the revision an event was stamped with against the current one. Unstamped
+    /// events, e.g. from IPC clients that omit the revision, are always current.
+    pub fn check(&self, revision: Option<u64>, current: u64) -> Result<Staleness, StaleEvent> {
+        match revision {
+            None => Ok(Staleness::Current),
+            Some(revision) if revision == current => Ok(Staleness::Current),
+            Some(_) if *self == StalePolicy::Rebase => Ok(Staleness::Rebased),
+            Some(revision) => Err(StaleEvent {
+                revision,
+                current,
+                policy: *self,
+            }),
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    events::{events::AppEvent, limits::Limits, person_changed::PersonEditor, sets::EventsSet},
+    localization::LocalizedText,
+    notify::Notify,
+    person_resource::{PersonField, PersonResource},
+};
+
+pub type TransactionId = u64;
+
+/// Starts an edit transaction: until it is committed or aborted, `PersonEditor` writes go to a
+/// staging copy of `PersonResource` and no `PersonChanged` is emitted.
+#[derive(BufferedEvent, Clone, Debug)]
+pub struct BeginEdit;
+
+/// Validates the staged person and applies all of its changes at once.
+#[derive(BufferedEvent, Clone, Debug)]
+pub struct CommitEdit;
+
+/// Discards the staged changes.
+#[derive(BufferedEvent, Clone, Debug)]
+pub struct AbortEdit;
+
+/// Emitted when a commit fails validation. Its changes are discarded.
+#[derive(BufferedEvent, Clone, Debug)]
+pub struct EditFailed {
+    pub transaction: TransactionId,
+    pub reason: String,
+}
+
+pub(crate) struct Staged {
+    pub id: TransactionId,
+    /// The committed person at `BeginEdit`, kept in sync with remote changes.
+    pub base: PersonResource,
+    pub person: PersonResource,
+    /// The event that last changed each staged field.
+    pub causes: HashMap<PersonField, AppEvent>,
+}
+
+/// The open edit transaction, if any.
+#[derive(Resource, Default)]
+pub struct Transaction {
+    next_id: TransactionId,
+    pub(crate) staged: Option<Staged>,
+}
+
+impl Transaction {
+    pub fn id(&self) -> Option<TransactionId> {
+        self.staged.as_ref().map(|staged| staged.id)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.staged.is_some()
+    }
+
+    /// The staged person, including the changes not committed yet.
+    pub fn staged(&self) -> Option<&PersonResource> {
+        self.staged.as_ref().map(|staged| &staged.person)
+    }
+
+    /// Number of fields the transaction changes so far.
+    pub fn pending(&self) -> usize {
+        self.staged
+            .as_ref()
+            .map_or(0, |staged| staged.base.diff(&staged.person).len())
+    }
+
+    pub(crate) fn open(&mut self, person: &PersonResource) -> bool {
+        if self.staged.is_some() {
+            return false;
+        }
+
+        self.next_id += 1;
+        self.staged = Some(Staged {
+            id: self.next_id,
+            base: person.clone(),
+            person: person.clone(),
+            causes: HashMap::new(),
+        });
+        true
+    }
+}
+
+/// Writes `BeginEdit`, `CommitEdit` and `AbortEdit`.
+#[derive(SystemParam)]
+pub struct TransactionWriter<'w> {
+    begin_writer: EventWriter<'w, BeginEdit>,
+    commit_writer: EventWriter<'w, CommitEdit>,
+    abort_writer: EventWriter<'w, AbortEdit>,
+}
+
+impl TransactionWriter<'_> {
+    pub fn begin(&mut self) {
+        self.begin_writer.write(BeginEdit);
+    }
+
+    pub fn commit(&mut self) {
+        self.commit_writer.write(CommitEdit);
+    }
+
+    pub fn abort(&mut self) {
+        self.abort_writer.write(AbortEdit);
+    }
+}
+
+/// Checks the person a transaction would commit, as a whole.
+pub fn validate(person: &PersonResource, limits: &Limits) -> Result<(), String> {
+    for (name, value, limits) in [
+        ("age", person.age, &limits.age),
+        ("counter", person.counter, &limits.counter),
+    ] {
+        if !limits.contains(value) {
+            return Err(format!(
+                "{name} {value} is outside {}..={}",
+                limits.min, limits.max
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub struct TransactionPlugin;
+
+impl Plugin for TransactionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Transaction>()
+            .add_event::<BeginEdit>()
+            .add_event::<CommitEdit>()
+            .add_event::<AbortEdit>()
+            .add_event::<EditFailed>()
+            .add_systems(Update, begin_edits.before(EventsSet::Route))
+            .add_systems(Update, finish_edits.in_set(EventsSet::Commit));
+    }
+}
+
+fn begin_edits(mut begin_reader: EventReader<BeginEdit>, mut person: PersonEditor) {
+    for _ in begin_reader.read() {
+        if !person.begin() {
+            warn!("An edit transaction is already open");
+        }
+    }
+}
+
+/// Commits run before aborts written in the same frame.
+fn finish_edits(
+    mut commit_reader: EventReader<CommitEdit>,
+    mut abort_reader: EventReader<AbortEdit>,
+    mut person: PersonEditor,
+    mut failed_writer: EventWriter<EditFailed>,
+    mut notify_writer: EventWriter<Notify>,
+    limits: Res<Limits>,
+) {
+    for _ in commit_reader.read() {
+        let Some(id) = person.transaction() else {
+            warn!("No edit transaction to commit");
+            continue;
+        };
+
+        if let Err(reason) = person.commit(|person| validate(person, &limits)) {
+            warn!("Edit transaction {id} failed: {reason}");
+            notify_writer.write(Notify::error(
+                LocalizedText::new("notify-edit-failed").with_arg("reason", reason.clone()),
+            ));
+            failed_writer.write(EditFailed {
+                transaction: id,
+                reason,
+            });
+        }
+    }
+
+    for _ in abort_reader.read() {
+        if person.transaction().is_none() {
+            warn!("No edit transaction to abort");
+            continue;
+        }
+        person.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        change_name::ChangeNameEvent,
+        events::{AppEventKind, EventsPlugin},
+        grow_older::GrowOlderEvent,
+        person_changed::{PersonChanged, PersonChangedPlugin},
+        prefill::PrefilledInputs,
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.insert_resource(Limits::default())
+            .add_plugins((MinimalPlugins, EventsPlugin::default(), PersonChangedPlugin))
+            .insert_resource(PersonResource::default());
+        app.update();
+        app
+    }
+
+    /// Opens a transaction, then grows the person older and renames them to `Ada` inside it.
+    fn stage_edits(app: &mut App) {
+        app.world_mut().write_event(BeginEdit);
+        app.update();
+        app.world_mut()
+            .resource_mut::<PrefilledInputs>()
+            .push(AppEventKind::ChangeName, "Ada".to_string());
+        app.world_mut()
+            .write_event(AppEvent::GrowOlder(GrowOlderEvent));
+        app.world_mut()
+            .write_event(AppEvent::ChangeName(ChangeNameEvent));
+        app.update();
+        assert_eq!(app.world().resource::<Transaction>().pending(), 2);
+    }
+
+    fn changes(app: &App) -> Vec<(PersonField, Option<TransactionId>)> {
+        app.world()
+            .resource::<Events<PersonChanged>>()
+            .iter_current_update_events()
+            .map(|changed| (changed.field, changed.transaction))
+            .collect()
+    }
+
+    fn failures(app: &App) -> usize {
+        app.world()
+            .resource::<Events<EditFailed>>()
+            .iter_current_update_events()
+            .count()
+    }
+
+    #[test]
+    fn commit_applies_all_changes_as_one_batch() {
+        let mut app = app();
+        stage_edits(&mut app);
+        assert!(changes(&app).is_empty());
+        assert_eq!(app.world().resource::<PersonResource>().age, 0);
+
+        let id = app.world().resource::<Transaction>().id();
+        app.world_mut().write_event(CommitEdit);
+        app.update();
+
+        let mut changes = changes(&app);
+        changes.sort_by_key(|(field, _)| *field as u8);
+        assert_eq!(changes, [(PersonField::Name, id), (PersonField::Age, id)]);
+        let person = app.world().resource::<PersonResource>();
+        assert_eq!((person.name.as_str(), person.age), ("Ada", 1));
+        assert!(!app.world().resource::<Transaction>().is_open());
+    }
+
+    #[test]
+    fn abort_leaves_the_person_unchanged() {
+        let mut app = app();
+        let before = app.world().resource::<PersonResource>().clone();
+        stage_edits(&mut app);
+
+        app.world_mut().write_event(AbortEdit);
+        app.update();
+
+        assert!(changes(&app).is_empty());
+        assert!(
+            before
+                .diff(app.world().resource::<PersonResource>())
+                .is_empty()
+        );
+        assert!(!app.world().resource::<Transaction>().is_open());
+    }
+
+    #[test]
+    fn failed_validation_applies_nothing() {
+        let mut app = app();
+        let before = app.world().resource::<PersonResource>().clone();
+        stage_edits(&mut app);
+
+        // The limits shrink while the transaction is open, so the staged age no longer fits.
+        app.world_mut().resource_mut::<Limits>().age.max = 0;
+        app.world_mut().write_event(CommitEdit);
+        app.update();
+
+        assert_eq!(failures(&app), 1);
+        assert!(changes(&app).is_empty());
+        assert!(
+            before
+                .diff(app.world().resource::<PersonResource>())
+                .is_empty()
+        );
+        assert!(!app.world().resource::<Transaction>().is_open());
+    }
+}
@@ -0,0 +1,521 @@
+use std::{
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{ecs::event::EventId, prelude::*};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    events::{
+        events::{AppEvent, AppEventKind, EventRouted},
+        person_changed::{ChangeOrigin, PersonChanged},
+        sets::EventsSet,
+        source::EventSource,
+        transaction::TransactionId,
+    },
+    localization::{Localization, LocalizedText},
+    person_resource::{PersonField, PersonValue},
+};
+
+/// Oldest entries are dropped beyond this many.
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+pub const HISTORY_CSV_PATH: &str = "history.csv";
+#[cfg(feature = "serde")]
+pub const HISTORY_JSON_PATH: &str = "history.json";
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldChange {
+    pub field: PersonField,
+    pub old: PersonValue,
+    pub new: PersonValue,
+}
+
+/// One routed event, one remote edit or one edit transaction, with the fields it changed.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: f64,
+    /// A single kind, except for a transaction, which lists the kinds of all events routed in it.
+    pub kinds: Vec<AppEventKind>,
+    pub transaction: Option<TransactionId>,
+    pub source: EventSource,
+    pub changes: Vec<FieldChange>,
+    /// The id on `EventRouted`, matched against `PersonChanged::routed`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    routed: Option<EventId<AppEvent>>,
+}
+
+impl HistoryEntry {
+    fn names(&self, separator: &str) -> String {
+        self.kinds
+            .iter()
+            .map(|kind| kind.info().name)
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+/// Every event applied this session, fed by `EventRouted` and `PersonChanged`.
+#[derive(Resource, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() == MAX_HISTORY_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Adds `kind` and `change` to the entry of transaction `id`, creating it if needed.
+    fn record_transaction(
+        &mut self,
+        id: TransactionId,
+        kind: AppEventKind,
+        source: EventSource,
+        change: Option<FieldChange>,
+    ) {
+        let entry = self
+            .entries
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.transaction == Some(id));
+        match entry {
+            Some(entry) => {
+                if !entry.kinds.contains(&kind) {
+                    entry.kinds.push(kind);
+                }
+                entry.changes.extend(change);
+            }
+            None => self.push(HistoryEntry {
+                timestamp: now(),
+                kinds: vec![kind],
+                transaction: Some(id),
+                source,
+                changes: change.into_iter().collect(),
+                routed: None,
+            }),
+        }
+    }
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// `HH:MM:SS` in UTC.
+fn format_time(timestamp: f64) -> String {
+    let seconds = timestamp as u64 % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per changed field, or a single row with empty field columns for an event that
+/// changed nothing.
+pub fn history_csv<'a>(entries: impl Iterator<Item = &'a HistoryEntry>) -> String {
+    let mut csv = String::from("timestamp,event,source,field,old,new\n");
+    for entry in entries {
+        let prefix = format!(
+            "{:.3},{},{}",
+            entry.timestamp,
+            entry.names("+"),
+            csv_cell(&entry.source.to_string())
+        );
+
+        if entry.changes.is_empty() {
+            csv.push_str(&format!("{prefix},,,\n"));
+        }
+        for change in &entry.changes {
+            csv.push_str(&format!(
+                "{prefix},{:?},{},{}\n",
+                change.field,
+                csv_cell(&change.old.to_string()),
+                csv_cell(&change.new.to_string())
+            ));
+        }
+    }
+    csv
+}
+
+/// `{"version":1,"event":[...]}`, stamped like every other serialized event.
+#[cfg(feature = "serde")]
+pub fn history_json<'a>(entries: impl Iterator<Item = &'a HistoryEntry>) -> String {
+    let entries: Vec<&HistoryEntry> = entries.collect();
+    serde_json::to_string_pretty(&crate::events::envelope::EventEnvelope::new(entries))
+        .unwrap_or_default()
+}
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<History>()
+            .add_systems(Update, record_history.in_set(EventsSet::Post));
+    }
+}
+
+fn record_history(
+    mut history: ResMut<History>,
+    mut routed_reader: EventReader<EventRouted>,
+    mut changed_reader: EventReader<PersonChanged>,
+) {
+    for routed in routed_reader.read() {
+        let kind = routed.event.kind();
+        match routed.transaction {
+            Some(id) => history.record_transaction(id, kind, routed.source.clone(), None),
+            None => history.push(HistoryEntry {
+                timestamp: now(),
+                kinds: vec![kind],
+                transaction: None,
+                source: routed.source.clone(),
+                changes: Vec::new(),
+                routed: Some(routed.id),
+            }),
+        }
+    }
+
+    // Consecutive remote changes with the same cause are one edit on the other instance.
+    let mut grouping_remote = false;
+    for changed in changed_reader.read() {
+        let kind = changed.cause.kind();
+        let change = FieldChange {
+            field: changed.field,
+            old: changed.old.clone(),
+            new: changed.new.clone(),
+        };
+
+        if let Some(id) = changed.transaction {
+            grouping_remote = false;
+            history.record_transaction(id, kind, EventSource::Other, Some(change));
+            continue;
+        }
+
+        match &changed.origin {
+            ChangeOrigin::Local => {
+                grouping_remote = false;
+                // Dialog results arrive frames after routing, so a change is matched to its entry
+                // by the id of the routed event.
+                let entry = changed.routed.and_then(|routed| {
+                    history
+                        .entries
+                        .iter_mut()
+                        .rev()
+                        .find(|entry| entry.routed == Some(routed))
+                });
+                match entry {
+                    Some(entry) => entry.changes.push(change),
+                    None => history.push(HistoryEntry {
+                        timestamp: now(),
+                        kinds: vec![kind],
+                        transaction: None,
+                        source: EventSource::Other,
+                        changes: vec![change],
+                        routed: changed.routed,
+                    }),
+                }
+            }
+            ChangeOrigin::Remote { user } => {
+                let source = EventSource::Network { user: user.clone() };
+                match history.entries.last_mut() {
+                    Some(entry)
+                        if grouping_remote && entry.kinds == [kind] && entry.source == source =>
+                    {
+                        entry.changes.push(change);
+                    }
+                    _ => history.push(HistoryEntry {
+                        timestamp: now(),
+                        kinds: vec![kind],
+                        transaction: None,
+                        source,
+                        changes: vec![change],
+                        routed: None,
+                    }),
+                }
+                grouping_remote = true;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum HistoryDock {
+    Right,
+    Bottom,
+    Floating,
+}
+
+#[derive(Resource)]
+struct HistoryPanel {
+    dock: HistoryDock,
+    hidden: Vec<AppEventKind>,
+    status: Option<LocalizedText>,
+}
+
+impl Default for HistoryPanel {
+    fn default() -> Self {
+        HistoryPanel {
+            dock: HistoryDock::Right,
+            hidden: Vec::new(),
+            status: None,
+        }
+    }
+}
+
+/// Egui panel listing the `History`, dockable to the right or bottom edge or floating.
+pub struct HistoryPanelPlugin;
+
+impl Plugin for HistoryPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HistoryPanel>()
+            .add_systems(EguiPrimaryContextPass, history_panel);
+    }
+}
+
+fn history_panel(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<HistoryPanel>,
+    history: Res<History>,
+    localization: Res<Localization>,
+) {
+    let ctx = contexts.ctx_mut().unwrap();
+    let title = localization.text("history-title");
+
+    let dock = panel.dock;
+    match dock {
+        HistoryDock::Right => {
+            egui::SidePanel::right("History Panel")
+                .resizable(true)
+                .default_width(340.0)
+                .show(ctx, |ui| {
+                    ui.heading(title);
+                    history_contents(ui, &mut panel, &history, &localization);
+                });
+        }
+        HistoryDock::Bottom => {
+            egui::TopBottomPanel::bottom("History Panel")
+                .resizable(true)
+                .default_height(220.0)
+                .show(ctx, |ui| {
+                    ui.heading(title);
+                    history_contents(ui, &mut panel, &history, &localization);
+                });
+        }
+        HistoryDock::Floating => {
+            egui::Window::new(title)
+                .id(egui::Id::new("History Window"))
+                .default_width(340.0)
+                .show(ctx, |ui| {
+                    history_contents(ui, &mut panel, &history, &localization);
+                });
+        }
+    }
+}
+
+fn history_contents(
+    ui: &mut egui::Ui,
+    panel: &mut HistoryPanel,
+    history: &History,
+    localization: &Localization,
+) {
+    ui.horizontal(|ui| {
+        ui.label(localization.text("history-dock"));
+        ui.selectable_value(
+            &mut panel.dock,
+            HistoryDock::Right,
+            localization.text("history-dock-right"),
+        );
+        ui.selectable_value(
+            &mut panel.dock,
+            HistoryDock::Bottom,
+            localization.text("history-dock-bottom"),
+        );
+        ui.selectable_value(
+            &mut panel.dock,
+            HistoryDock::Floating,
+            localization.text("history-dock-floating"),
+        );
+    });
+
+    ui.horizontal_wrapped(|ui| {
+        for kind in AppEventKind::ALL {
+            let mut shown = !panel.hidden.contains(kind);
+            if ui
+                .checkbox(&mut shown, localization.text(kind.info().name_id))
+                .changed()
+            {
+                if shown {
+                    panel.hidden.retain(|hidden| hidden != kind);
+                } else {
+                    panel.hidden.push(*kind);
+                }
+            }
+        }
+    });
+
+    let hidden = &panel.hidden.clone();
+    let shown = || {
+        history
+            .entries()
+            .iter()
+            .filter(move |entry| entry.kinds.iter().any(|kind| !hidden.contains(kind)))
+    };
+
+    ui.horizontal(|ui| {
+        if ui.button(localization.text("history-export-csv")).clicked() {
+            panel.status = Some(export(HISTORY_CSV_PATH, history_csv(shown())));
+        }
+        #[cfg(feature = "serde")]
+        if ui
+            .button(localization.text("history-export-json"))
+            .clicked()
+        {
+            panel.status = Some(export(HISTORY_JSON_PATH, history_json(shown())));
+        }
+    });
+    if let Some(status) = &panel.status {
+        ui.weak(localization.format(status));
+    }
+
+    ui.separator();
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        let mut empty = true;
+        for entry in shown().rev() {
+            empty = false;
+            let events = entry
+                .kinds
+                .iter()
+                .map(|kind| localization.text(kind.info().name_id))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let title = match entry.transaction {
+                Some(_) => localization
+                    .format(&LocalizedText::new("history-transaction").with_arg("events", events)),
+                None => events,
+            };
+            ui.label(
+                egui::RichText::new(format!(
+                    "{}  {}  ·  {}",
+                    format_time(entry.timestamp),
+                    title,
+                    entry.source
+                ))
+                .strong(),
+            );
+
+            if entry.changes.is_empty() {
+                ui.weak(localization.text("history-no-change"));
+            }
+            for change in &entry.changes {
+                ui.label(format!(
+                    "{:?}: {} → {}",
+                    change.field, change.old, change.new
+                ));
+            }
+            ui.add_space(4.0);
+        }
+
+        if empty {
+            ui.weak(localization.text("history-empty"));
+        }
+    });
+}
+
+fn export(path: &'static str, contents: String) -> LocalizedText {
+    match fs::write(path, contents) {
+        Ok(()) => LocalizedText::new("history-exported").with_arg("path", path),
+        Err(error) => {
+            LocalizedText::new("history-export-failed").with_arg("error", error.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::{
+            change_name::ChangeNameEvent, events::EventsPlugin,
+            person_changed::PersonChangedPlugin, prefill::PrefilledInputs,
+        },
+        person_resource::PersonResource,
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            EventsPlugin::default(),
+            PersonChangedPlugin,
+            HistoryPlugin,
+        ))
+        .insert_resource(PersonResource::default());
+        app.update();
+        app
+    }
+
+    /// Routes one `ChangeName` per input in the same frame and returns the names each entry
+    /// changed to.
+    fn change_names(app: &mut App, inputs: [&str; 2]) -> Vec<Vec<PersonValue>> {
+        for input in inputs {
+            app.world_mut()
+                .resource_mut::<PrefilledInputs>()
+                .push(AppEventKind::ChangeName, input.to_string());
+            app.world_mut()
+                .write_event(AppEvent::ChangeName(ChangeNameEvent));
+        }
+        app.update();
+
+        let entries = app.world().resource::<History>().entries();
+        entries[entries.len() - 2..]
+            .iter()
+            .map(|entry| {
+                entry
+                    .changes
+                    .iter()
+                    .map(|change| change.new.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn changes_attach_to_the_event_that_made_them() {
+        let mut app = app();
+        let name = app.world().resource::<PersonResource>().name.clone();
+
+        assert_eq!(
+            change_names(&mut app, [&name, "Bob"]),
+            [vec![], vec![PersonValue::Text("Bob".to_string())]]
+        );
+        assert_eq!(
+            change_names(&mut app, ["Alice", "Alice"]),
+            [vec![PersonValue::Text("Alice".to_string())], vec![]]
+        );
+    }
+}
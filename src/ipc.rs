@@ -0,0 +1,323 @@
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{
+        envelope::EventEnvelope,
+        events::{AppEvent, EventRejected},
+        person_changed::PersonChanged,
+        prefill::PrefilledInputs,
+        sets::EventsSet,
+        source::{AppEventWriter, EventSource},
+    },
+    json_lines::JsonLineStream,
+    localization::Localization,
+    person_resource::{PersonField, PersonResource, PersonValue},
+    scripting::ScriptRunner,
+};
+
+pub const DEFAULT_IPC_PORT: u16 = 7878;
+
+/// A line sent by a client, e.g.
+/// `{"type":"event","version":1,"event":{"type":"ChangeName","data":null},"input":"Alice"}`.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IpcRequest {
+    Event {
+        #[serde(flatten)]
+        event: EventEnvelope<AppEvent>,
+        /// The dialog result, for events that open a dialog.
+        #[serde(default)]
+        input: Option<String>,
+    },
+    Script {
+        script: String,
+    },
+    Snapshot,
+}
+
+/// A line sent to every client, e.g. `{"type":"rejected","event":"GrowOlder","reason":"..."}`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IpcMessage {
+    Snapshot {
+        person: PersonResource,
+    },
+    Changed {
+        field: PersonField,
+        old: PersonValue,
+        new: PersonValue,
+        cause: &'static str,
+    },
+    Rejected {
+        event: &'static str,
+        reason: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Resource)]
+struct IpcServer {
+    listener: TcpListener,
+    clients: Vec<JsonLineStream>,
+}
+
+/// Listens on a localhost TCP socket for newline-delimited JSON requests and streams back
+/// `PersonResource` snapshots and change notifications. Received events go straight to the
+/// router; scripts require `ScriptingPlugin`.
+pub struct IpcPlugin {
+    pub address: SocketAddr,
+}
+
+impl Default for IpcPlugin {
+    fn default() -> Self {
+        IpcPlugin {
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_IPC_PORT)),
+        }
+    }
+}
+
+impl Plugin for IpcPlugin {
+    fn build(&self, app: &mut App) {
+        let listener = match TcpListener::bind(self.address)
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+        {
+            Ok(listener) => listener,
+            Err(error) => {
+                error!("Could not open IPC socket on {}: {error}", self.address);
+                return;
+            }
+        };
+        info!("IPC socket listening on {}", self.address);
+
+        app.insert_resource(IpcServer {
+            listener,
+            clients: Vec::new(),
+        })
+        .add_systems(
+            Update,
+            (accept_ipc_clients, read_ipc_requests)
+                .chain()
+                .before(EventsSet::Route),
+        )
+        .add_systems(Update, broadcast_ipc_messages.in_set(EventsSet::Post));
+    }
+}
+
+fn accept_ipc_clients(mut server: ResMut<IpcServer>, person: Res<PersonResource>) {
+    loop {
+        match server.listener.accept() {
+            Ok((stream, address)) => match JsonLineStream::new(stream) {
+                Ok(mut client) => {
+                    debug!("IPC client connected from {address}");
+                    client.send(&IpcMessage::Snapshot {
+                        person: person.clone(),
+                    });
+                    server.clients.push(client);
+                }
+                Err(error) => warn!("Could not accept IPC client {address}: {error}"),
+            },
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            Err(error) => {
+                warn!("IPC accept failed: {error}");
+                break;
+            }
+        }
+    }
+}
+
+fn read_ipc_requests(
+    mut server: ResMut<IpcServer>,
+    mut runner: ResMut<ScriptRunner>,
+    mut prefilled: ResMut<PrefilledInputs>,
+    mut event_writer: AppEventWriter,
+    person: Res<PersonResource>,
+) {
+    for client in &mut server.clients {
+        for line in client.read_lines() {
+            let request = match serde_json::from_str::<IpcRequest>(&line) {
+                Ok(request) => request,
+                Err(error) => {
+                    client.send(&IpcMessage::Error {
+                        message: error.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            match request {
+                IpcRequest::Event { event, input } => {
+                    let event = match event.into_event() {
+                        Ok(event) => event,
+                        Err(message) => {
+                            client.send(&IpcMessage::Error { message });
+                            continue;
+                        }
+                    };
+                    let kind = event.kind();
+                    match input {
+                        Some(_) if !kind.info().needs_dialog => client.send(&IpcMessage::Error {
+                            message: format!("`{}` has no dialog to fill", kind.info().name),
+                        }),
+                        input => {
+                            if let Some(input) = input {
+                                prefilled.push(kind, input);
+                            }
+                            event_writer.write(event, EventSource::Ipc);
+                        }
+                    }
+                }
+                IpcRequest::Script { script } => match script.parse() {
+                    Ok(script) => runner.run(script),
+                    Err(message) => client.send(&IpcMessage::Error { message }),
+                },
+                IpcRequest::Snapshot => client.send(&IpcMessage::Snapshot {
+                    person: person.clone(),
+                }),
+            }
+        }
+    }
+}
+
+fn broadcast_ipc_messages(
+    mut server: ResMut<IpcServer>,
+    mut changed_reader: EventReader<PersonChanged>,
+    mut rejected_reader: EventReader<EventRejected>,
+    person: Res<PersonResource>,
+    localization: Option<Res<Localization>>,
+) {
+    let mut messages = Vec::new();
+    for changed in changed_reader.read() {
+        messages.push(IpcMessage::Changed {
+            field: changed.field,
+            old: changed.old.clone(),
+            new: changed.new.clone(),
+            cause: changed.cause.kind().info().name,
+        });
+    }
+    if !messages.is_empty() {
+        messages.push(IpcMessage::Snapshot {
+            person: person.clone(),
+        });
+    }
+    for rejected in rejected_reader.read() {
+        messages.push(IpcMessage::Rejected {
+            event: rejected.event.kind().info().name,
+            reason: match &localization {
+                Some(localization) => localization.format(&rejected.reason),
+                None => rejected.reason.to_string(),
+            },
+        });
+    }
+
+    for client in &mut server.clients {
+        for message in &messages {
+            client.send(message);
+        }
+        client.flush();
+    }
+    server.clients.retain(|client| !client.is_closed());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpStream,
+        time::Duration,
+    };
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        events::{
+            envelope::EVENT_FORMAT_VERSION, events::EventsPlugin,
+            person_changed::PersonChangedPlugin,
+        },
+        scripting::ScriptingPlugin,
+    };
+
+    /// Complete lines received so far; a partial line stays in `pending`.
+    fn read_messages(reader: &mut BufReader<TcpStream>, pending: &mut String) -> Vec<Value> {
+        let mut messages = Vec::new();
+        while let Ok(read) = reader.read_line(pending) {
+            if read == 0 || !pending.ends_with('\n') {
+                break;
+            }
+            messages.push(serde_json::from_str(pending.trim()).unwrap());
+            pending.clear();
+        }
+        messages
+    }
+
+    #[test]
+    fn client_event_changes_person() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            EventsPlugin::default(),
+            PersonChangedPlugin,
+            ScriptingPlugin,
+            IpcPlugin {
+                address: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            },
+        ))
+        .insert_resource(PersonResource::default());
+        let address = app
+            .world()
+            .resource::<IpcServer>()
+            .listener
+            .local_addr()
+            .unwrap();
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(20)))
+            .unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut pending = String::new();
+
+        // The counter is taken from the payload, not from the person.
+        let request = serde_json::json!({
+            "type": "event",
+            "version": EVENT_FORMAT_VERSION,
+            "event": {"type": "ChangeCounter", "data": {"counter": 40}},
+            "input": "0"
+        });
+        writeln!(stream, "{request}").unwrap();
+
+        let mut messages = Vec::new();
+        for _ in 0..10 {
+            app.update();
+            messages.extend(read_messages(&mut reader, &mut pending));
+            if messages.iter().any(|message| message["type"] == "changed") {
+                break;
+            }
+        }
+
+        assert_eq!(messages[0]["type"], "snapshot");
+        assert_eq!(messages[0]["person"]["counter"], 0);
+
+        let changed = messages
+            .iter()
+            .position(|message| message["type"] == "changed")
+            .expect("no change was reported");
+        assert_eq!(messages[changed]["field"], "Counter");
+        assert_eq!(messages[changed]["cause"], "ChangeCounter");
+        assert_eq!(messages[changed]["old"]["Number"], 0);
+
+        // ChangeCounter adds 1 to 99 at random.
+        let snapshot = &messages[changed + 1];
+        assert_eq!(snapshot["type"], "snapshot");
+        let counter = snapshot["person"]["counter"].as_u64().unwrap();
+        assert!((41..140).contains(&counter), "counter is {counter}");
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    io::{self, Read, Write},
+    net::TcpStream,
+};
+
+use bevy::log::error;
+use serde::Serialize;
+
+/// A non-blocking TCP stream carrying newline-delimited JSON, polled once per frame.
+pub struct JsonLineStream {
+    stream: TcpStream,
+    inbound: Vec<u8>,
+    outbound: Vec<u8>,
+    closed: bool,
+}
+
+impl JsonLineStream {
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(JsonLineStream {
+            stream,
+            inbound: Vec::new(),
+            outbound: Vec::new(),
+            closed: false,
+        })
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Queues `message` as one line; it is written on the next `flush`.
+    pub fn send(&mut self, message: &impl Serialize) {
+        match serde_json::to_vec(message) {
+            Ok(line) => {
+                self.outbound.extend(line);
+                self.outbound.push(b'\n');
+            }
+            Err(error) => error!("Could not encode JSON line: {error}"),
+        }
+    }
+
+    /// Complete lines received since the last call; a partial line stays buffered.
+    pub fn read_lines(&mut self) -> Vec<String> {
+        let mut buffer = [0; 1024];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(read) => self.inbound.extend_from_slice(&buffer[..read]),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(_) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        while let Some(end) = self.inbound.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.inbound.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        lines
+    }
+
+    pub fn flush(&mut self) {
+        while !self.outbound.is_empty() && !self.closed {
+            match self.stream.write(&self.outbound) {
+                Ok(0) => self.closed = true,
+                Ok(written) => {
+                    self.outbound.drain(..written);
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(_) => self.closed = true,
+            }
+        }
+    }
+}
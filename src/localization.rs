@@ -0,0 +1,356 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use fluent::{FluentArgs, FluentResource, FluentValue, concurrent::FluentBundle};
+use serde::{Deserialize, Deserializer};
+use unic_langid::LanguageIdentifier;
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+pub const LOCALES: [&str; 2] = ["en-US", "de-DE"];
+
+/// Raw `.ftl` source, loaded from `assets/locales/<locale>/main.ftl`.
+#[derive(Asset, TypePath)]
+pub struct FluentSource(String);
+
+#[derive(Default)]
+struct FluentSourceLoader;
+
+impl AssetLoader for FluentSourceLoader {
+    type Asset = FluentSource;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        Ok(FluentSource(source))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
+
+/// A message id plus the arguments it is formatted with.
+///
+/// Deserializes from a bare id, `"dialog-name-title"`, or from an id with arguments,
+/// `(id: "dialog-counter-title", args: {"max": 100})`.
+#[derive(Clone, Debug)]
+pub struct LocalizedText {
+    id: Cow<'static, str>,
+    args: Vec<(Cow<'static, str>, Arg)>,
+}
+
+#[derive(Clone, Debug)]
+enum Arg {
+    Value(FluentValue<'static>),
+    Text(LocalizedText),
+}
+
+impl LocalizedText {
+    pub fn new(id: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            id: id.into(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with_arg(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl Into<FluentValue<'static>>,
+    ) -> Self {
+        self.args.push((name.into(), Arg::Value(value.into())));
+        self
+    }
+
+    /// Adds an argument that is formatted in the same locale as the message itself.
+    pub fn with_text_arg(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        text: LocalizedText,
+    ) -> Self {
+        self.args.push((name.into(), Arg::Text(text)));
+        self
+    }
+}
+
+/// The id and arguments, for logs and tools that run without a locale.
+impl fmt::Display for LocalizedText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        for (index, (name, arg)) in self.args.iter().enumerate() {
+            let separator = if index == 0 { " (" } else { ", " };
+            match arg {
+                Arg::Value(FluentValue::String(value)) => write!(f, "{separator}{name}: {value}")?,
+                Arg::Value(FluentValue::Number(value)) => {
+                    write!(f, "{separator}{name}: {}", value.as_string())?
+                }
+                Arg::Value(_) => write!(f, "{separator}{name}")?,
+                Arg::Text(text) => write!(f, "{separator}{name}: {text}")?,
+            }
+        }
+        if !self.args.is_empty() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalizedText {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RonArg {
+            Number(f64),
+            Text(String),
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Text {
+            Id(String),
+            WithArgs {
+                id: String,
+                #[serde(default)]
+                args: BTreeMap<String, RonArg>,
+            },
+        }
+
+        Ok(match Text::deserialize(deserializer)? {
+            Text::Id(id) => LocalizedText::new(id),
+            Text::WithArgs { id, args } => {
+                args.into_iter()
+                    .fold(LocalizedText::new(id), |text, (name, arg)| match arg {
+                        RonArg::Number(number) => text.with_arg(name, number),
+                        RonArg::Text(value) => text.with_arg(name, value),
+                    })
+            }
+        })
+    }
+}
+
+/// The active locale and its Fluent bundle.
+///
+/// Messages fall back to their id while the bundle for the active locale is still loading.
+#[derive(Resource)]
+pub struct Localization {
+    locale: LanguageIdentifier,
+    sources: HashMap<LanguageIdentifier, Handle<FluentSource>>,
+    bundle: Option<FluentBundle<FluentResource>>,
+}
+
+impl Localization {
+    pub fn locale(&self) -> &LanguageIdentifier {
+        &self.locale
+    }
+
+    pub fn set_locale(&mut self, locale: LanguageIdentifier) {
+        if self.locale != locale {
+            self.locale = locale;
+            self.bundle = None;
+        }
+    }
+
+    pub fn text(&self, id: &str) -> String {
+        self.format_message(id, None)
+    }
+
+    pub fn format(&self, text: &LocalizedText) -> String {
+        let mut args = FluentArgs::new();
+        for (name, arg) in &text.args {
+            let value = match arg {
+                Arg::Value(value) => value.clone(),
+                Arg::Text(text) => self.format(text).into(),
+            };
+            args.set(name.clone(), value);
+        }
+        self.format_message(&text.id, Some(&args))
+    }
+
+    fn format_message(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        let Some(bundle) = &self.bundle else {
+            return id.to_string();
+        };
+        let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+            return id.to_string();
+        };
+
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        for error in errors {
+            warn!("Failed to format {id}: {error}");
+        }
+        text.into_owned()
+    }
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self {
+            locale: DEFAULT_LOCALE.parse().unwrap(),
+            sources: HashMap::new(),
+            bundle: None,
+        }
+    }
+}
+
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<FluentSource>()
+            .init_asset_loader::<FluentSourceLoader>()
+            .init_resource::<Localization>()
+            .add_systems(Startup, load_locales)
+            .add_systems(Update, build_bundle);
+    }
+}
+
+fn load_locales(mut localization: ResMut<Localization>, asset_server: Res<AssetServer>) {
+    for locale in LOCALES {
+        localization.sources.insert(
+            locale.parse().unwrap(),
+            asset_server.load(format!("locales/{locale}/main.ftl")),
+        );
+    }
+}
+
+fn build_bundle(
+    mut localization: ResMut<Localization>,
+    mut asset_events: EventReader<AssetEvent<FluentSource>>,
+    sources: Res<Assets<FluentSource>>,
+) {
+    let Some(handle) = localization.sources.get(&localization.locale).cloned() else {
+        return;
+    };
+
+    let source_changed = asset_events
+        .read()
+        .filter(|event| event.is_loaded_with_dependencies(&handle) || event.is_modified(&handle))
+        .count()
+        > 0;
+    if localization.bundle.is_some() && !source_changed {
+        return;
+    }
+
+    let Some(FluentSource(source)) = sources.get(&handle) else {
+        return;
+    };
+
+    let resource = match FluentResource::try_new(source.clone()) {
+        Ok(resource) => resource,
+        Err((resource, errors)) => {
+            for error in errors {
+                warn!("Invalid message in {}: {error:?}", localization.locale);
+            }
+            resource
+        }
+    };
+
+    let mut bundle = FluentBundle::new_concurrent(vec![localization.locale.clone()]);
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        for error in errors {
+            warn!("Duplicate message in {}: {error}", localization.locale);
+        }
+    }
+
+    localization.bundle = Some(bundle);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use crate::events::dialog_definition::DialogDefinition;
+
+    fn localization(locale: &str) -> Localization {
+        let path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("assets/locales/{locale}/main.ftl"));
+        let resource = FluentResource::try_new(fs::read_to_string(path).unwrap())
+            .map_err(|(_, errors)| errors)
+            .unwrap();
+        let mut bundle = FluentBundle::new_concurrent(vec![locale.parse().unwrap()]);
+        bundle.set_use_isolating(false);
+        bundle.add_resource(resource).unwrap();
+
+        Localization {
+            locale: locale.parse().unwrap(),
+            sources: HashMap::new(),
+            bundle: Some(bundle),
+        }
+    }
+
+    #[test]
+    fn texts_deserialize_with_and_without_args() {
+        let localization = localization(DEFAULT_LOCALE);
+
+        let text: LocalizedText = ron::from_str(r#""button-cancel""#).unwrap();
+        assert_eq!(localization.format(&text), "Cancel");
+
+        let text: LocalizedText =
+            ron::from_str(r#"(id: "dialog-invalid-range", args: {"min": 1, "max": "ten"})"#)
+                .unwrap();
+        assert_eq!(localization.format(&text), "Must be between 1 and ten");
+    }
+
+    #[test]
+    fn text_args_are_formatted_in_the_same_locale() {
+        let text = LocalizedText::new("notify-handler-failed")
+            .with_text_arg("event", LocalizedText::new("event-GrowOlder"))
+            .with_arg("error", "too old");
+
+        assert_eq!(
+            localization("de-DE").format(&text),
+            "Älter werden fehlgeschlagen: too old"
+        );
+        assert_eq!(
+            text.to_string(),
+            "notify-handler-failed (event: event-GrowOlder, error: too old)"
+        );
+    }
+
+    #[test]
+    fn bundled_dialog_texts_exist_in_every_locale() {
+        let dialogs = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/dialogs");
+        for locale in LOCALES {
+            let localization = localization(locale);
+            for entry in fs::read_dir(&dialogs).unwrap() {
+                let path = entry.unwrap().path();
+                let definition: DialogDefinition =
+                    ron::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+                let texts = [
+                    &definition.title,
+                    &definition.positive_action,
+                    &definition.negative_action,
+                ];
+                for text in texts.into_iter().chain(&definition.hint) {
+                    let formatted = localization.format(text);
+                    assert_ne!(formatted, text.id, "{} in {locale}", path.display());
+                    assert!(
+                        !formatted.contains('{'),
+                        "{} in {locale} lacks an argument: {formatted}",
+                        path.display()
+                    );
+                }
+            }
+        }
+    }
+}
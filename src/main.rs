@@ -2,12 +2,9 @@
 
 use bevy::{
     DefaultPlugins,
-    app::{App, Startup},
+    app::{App, AppExit, Startup},
     camera::Camera2d,
-    ecs::{
-        event::EventWriter,
-        system::{Commands, Res},
-    },
+    ecs::system::{Commands, Res, ResMut},
 };
 use bevy_egui::{
     EguiContexts, EguiPlugin, EguiPrimaryContextPass,
@@ -15,79 +12,289 @@ use bevy_egui::{
 };
 
 use crate::{
+    command_palette::CommandPalettePlugin,
+    derived::{DerivedFields, DerivedFieldsPlugin},
     events::{
         change_color::OpenColorPickerEvent,
         change_counter::ChangeCounterEvent,
         change_location::ChangeLocationEvent,
         change_name::ChangeNameEvent,
-        events::{AppEvent, EventsPlugin},
+        dialog_backend::DialogBackend,
+        events::{AppEvent, EventAvailability, EventsPlugin},
         grow_older::GrowOlderEvent,
+        mode::EventsMode,
+        person_changed::{PersonChangedPlugin, PersonView},
+        source::{AppEventWriter, EventSource},
+        transaction::TransactionWriter,
     },
+    history::{HistoryPanelPlugin, HistoryPlugin},
+    localization::{LOCALES, Localization, LocalizationPlugin, LocalizedText},
+    notify::NotifyPlugin,
     person_resource::PersonResource,
+    scheduler::{SchedulePanelPlugin, SchedulerPlugin},
+    scripting::{ScriptPanelPlugin, ScriptingPlugin},
+    shortcuts::{Shortcuts, ShortcutsPlugin},
 };
 
+#[cfg(feature = "serde")]
+mod cli;
+mod command_palette;
+mod derived;
 mod events;
+mod history;
+#[cfg(feature = "ipc")]
+mod ipc;
+#[cfg(any(feature = "ipc", feature = "relay"))]
+mod json_lines;
+mod localization;
+mod notify;
+#[cfg(feature = "serde")]
+mod persistence;
 mod person_resource;
+#[cfg(feature = "relay")]
+mod replication;
+#[cfg(feature = "serde")]
+mod rules;
+mod scheduler;
+mod scripting;
+mod shortcuts;
+
+fn main() -> AppExit {
+    let mut args = std::env::args().skip(1).peekable();
+    #[cfg(feature = "serde")]
+    if args.peek().is_some_and(|arg| arg == "apply") {
+        return cli::apply(args.skip(1));
+    }
+
+    let args: Vec<String> = args.collect();
+    let mode = if args.iter().any(|arg| arg == "--read-only") {
+        EventsMode::ReadOnly
+    } else {
+        EventsMode::Editable
+    };
+    let dialogs = if args.iter().any(|arg| arg == "--bevy-ui-dialogs") {
+        DialogBackend::BevyUi
+    } else {
+        DialogBackend::Egui
+    };
 
-fn main() {
-    App::new()
-        .insert_resource(PersonResource::default())
-        .add_plugins((DefaultPlugins, EventsPlugin, EguiPlugin::default()))
+    let mut app = App::new();
+    app.insert_resource(PersonResource::default())
+        .insert_resource(mode)
+        .add_plugins((
+            DefaultPlugins,
+            EventsPlugin { dialogs },
+            PersonChangedPlugin,
+            LocalizationPlugin,
+            CommandPalettePlugin,
+            ShortcutsPlugin,
+            ScriptingPlugin,
+            ScriptPanelPlugin,
+            HistoryPlugin,
+            HistoryPanelPlugin,
+            NotifyPlugin,
+            DerivedFieldsPlugin,
+            SchedulerPlugin::default(),
+            SchedulePanelPlugin,
+            EguiPlugin::default(),
+        ))
         .add_systems(Startup, startup)
-        .add_systems(EguiPrimaryContextPass, render_ui)
-        .run();
+        .add_systems(EguiPrimaryContextPass, render_ui);
+
+    #[cfg(feature = "serde")]
+    app.add_plugins((rules::RulesPlugin, rules::RulesPanelPlugin));
+
+    #[cfg(feature = "ipc")]
+    app.add_plugins(ipc::IpcPlugin::default());
+
+    #[cfg(feature = "relay")]
+    if let Some(replication) = replication::ReplicationPlugin::from_env() {
+        app.add_plugins(replication);
+    }
+
+    app.run()
 }
 
 fn startup(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_ui(
     mut context: EguiContexts,
-    mut event_writer: EventWriter<AppEvent>,
-    person: Res<PersonResource>,
+    mut event_writer: AppEventWriter,
+    mut transaction_writer: TransactionWriter,
+    mut localization: ResMut<Localization>,
+    person: PersonView,
+    availability: Res<EventAvailability>,
+    shortcuts: Res<Shortcuts>,
+    derived: Res<DerivedFields>,
 ) {
     let ctx = context.ctx_mut().unwrap();
-    egui::Window::new("Person")
+    egui::Window::new(localization.text("person-window-title"))
         .id(Id::new("Person Window"))
         .anchor(Align2::CENTER_CENTER, egui::Vec2::new(0.0, 0.0))
         .fixed_size(egui::Vec2::new(500.0, 500.0))
         .show(ctx, |ui| {
-            ui.heading("Person");
+            ui.heading(localization.text("person-window-title"));
             ui.add_space(16.0);
 
-            ui.label(format!("Name: {}", &person.name));
-            if ui.button("Change").clicked() {
-                event_writer.write(AppEvent::ChangeName(ChangeNameEvent));
-            }
+            ui.label(
+                localization.format(
+                    &LocalizedText::new("person-name").with_arg("name", person.name.clone()),
+                ),
+            );
+            event_button(
+                ui,
+                &localization.text("button-change"),
+                AppEvent::ChangeName(ChangeNameEvent),
+                &localization,
+                &availability,
+                &shortcuts,
+                &mut event_writer,
+            );
             ui.add_space(8.0);
 
-            ui.label(format!("Age: {}", person.age.to_string()));
-            if ui.button("Grow old").clicked() {
-                event_writer.write(AppEvent::GrowOlder(GrowOlderEvent));
-            }
+            ui.label(
+                localization.format(&LocalizedText::new("person-age").with_arg("age", person.age)),
+            );
+            event_button(
+                ui,
+                &localization.text("button-grow-old"),
+                AppEvent::GrowOlder(GrowOlderEvent),
+                &localization,
+                &availability,
+                &shortcuts,
+                &mut event_writer,
+            );
             ui.add_space(8.0);
 
-            ui.label(format!("Location: {}", &person.location));
-            if ui.button("Change").clicked() {
-                event_writer.write(AppEvent::ChangeLocation(ChangeLocationEvent));
-            }
+            ui.label(
+                localization.format(
+                    &LocalizedText::new("person-location")
+                        .with_arg("location", person.location.clone()),
+                ),
+            );
+            event_button(
+                ui,
+                &localization.text("button-change"),
+                AppEvent::ChangeLocation(ChangeLocationEvent),
+                &localization,
+                &availability,
+                &shortcuts,
+                &mut event_writer,
+            );
             ui.add_space(8.0);
 
-            ui.label(format!("Color: {:?}", &person.color));
-            if ui.button("Change").clicked() {
-                event_writer.write(AppEvent::OpenColorPicker(OpenColorPickerEvent {
-                    color: person.color.clone(),
-                }));
-            }
+            ui.label(
+                localization.format(
+                    &LocalizedText::new("person-color")
+                        .with_arg("color", format!("{:?}", person.color)),
+                ),
+            );
+            event_button(
+                ui,
+                &localization.text("button-change"),
+                AppEvent::OpenColorPicker(OpenColorPickerEvent::from(&*person)),
+                &localization,
+                &availability,
+                &shortcuts,
+                &mut event_writer,
+            );
             ui.add_space(8.0);
 
-            ui.label(format!("Counter: {}", &person.counter.to_string()));
-            if ui.button("Randomize").clicked() {
-                event_writer.write(AppEvent::ChangeCounter(ChangeCounterEvent {
-                    counter: person.counter,
-                }));
+            ui.label(
+                localization.format(
+                    &LocalizedText::new("person-counter").with_arg("counter", person.counter),
+                ),
+            );
+            event_button(
+                ui,
+                &localization.text("button-randomize"),
+                AppEvent::ChangeCounter(ChangeCounterEvent::from(&*person)),
+                &localization,
+                &availability,
+                &shortcuts,
+                &mut event_writer,
+            );
+            ui.add_space(16.0);
+
+            for (label_id, value) in derived.values() {
+                ui.weak(format!(
+                    "{}: {}",
+                    localization.text(label_id),
+                    localization.format(value)
+                ));
+            }
+            ui.add_space(16.0);
+
+            ui.horizontal(|ui| {
+                let transaction = person.transaction();
+                if !transaction.is_open() {
+                    if ui
+                        .button(localization.text("person-edit-begin"))
+                        .on_hover_text(localization.text("person-edit-begin-hint"))
+                        .clicked()
+                    {
+                        transaction_writer.begin();
+                    }
+                    return;
+                }
+
+                ui.label(
+                    localization.format(
+                        &LocalizedText::new("person-edit-pending")
+                            .with_arg("count", transaction.pending()),
+                    ),
+                );
+                if ui.button(localization.text("person-edit-commit")).clicked() {
+                    transaction_writer.commit();
+                }
+                if ui.button(localization.text("person-edit-abort")).clicked() {
+                    transaction_writer.abort();
+                }
+            });
+            ui.add_space(16.0);
+
+            let mut locale = localization.locale().clone();
+            ui.horizontal(|ui| {
+                ui.label(localization.text("person-language"));
+                egui::ComboBox::from_id_salt("Locale")
+                    .selected_text(locale.to_string())
+                    .show_ui(ui, |ui| {
+                        for available in LOCALES {
+                            let available = available.parse().unwrap();
+                            let label = format!("{available}");
+                            ui.selectable_value(&mut locale, available, label);
+                        }
+                    });
+            });
+            if &locale != localization.locale() {
+                localization.set_locale(locale);
             }
-            ui.add_space(8.0);
         });
 }
+
+fn event_button(
+    ui: &mut egui::Ui,
+    label: &str,
+    event: AppEvent,
+    localization: &Localization,
+    availability: &EventAvailability,
+    shortcuts: &Shortcuts,
+    event_writer: &mut AppEventWriter,
+) {
+    let response = match availability.check(&event) {
+        Ok(()) => match shortcuts.chord_for(event.kind()) {
+            Some(chord) => ui.button(label).on_hover_text(chord.to_string()),
+            None => ui.button(label),
+        },
+        Err(reason) => ui
+            .add_enabled(false, egui::Button::new(label))
+            .on_disabled_hover_text(localization.format(&reason)),
+    };
+
+    if response.clicked() {
+        event_writer.write(event, EventSource::Button);
+    }
+}
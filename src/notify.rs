@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts, EguiPrimaryContextPass,
+    egui::{self, Align2, Color32, Frame, Margin, RichText},
+};
+
+use crate::{
+    events::{
+        events::AppEvent,
+        sets::EventsSet,
+        source::{AppEventWriter, EventSource},
+    },
+    localization::{Localization, LocalizedText},
+};
+
+/// Toasts beyond this many push out the oldest.
+const MAX_TOASTS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Seconds a toast stays on screen.
+    fn duration(&self) -> f32 {
+        match self {
+            Severity::Info | Severity::Success => 4.0,
+            Severity::Warning => 6.0,
+            Severity::Error => 8.0,
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        match self {
+            Severity::Info => Color32::from_rgb(60, 90, 140),
+            Severity::Success => Color32::from_rgb(60, 120, 60),
+            Severity::Warning => Color32::from_rgb(170, 120, 40),
+            Severity::Error => Color32::from_rgb(170, 50, 60),
+        }
+    }
+}
+
+/// A button on a toast that dispatches `event` when clicked.
+pub struct NotifyAction {
+    pub label: LocalizedText,
+    pub event: AppEvent,
+}
+
+/// A message for the user, shown as a toast by `NotifyPlugin`.
+#[derive(BufferedEvent)]
+pub struct Notify {
+    pub message: LocalizedText,
+    pub severity: Severity,
+    pub action: Option<NotifyAction>,
+}
+
+impl Notify {
+    pub fn new(severity: Severity, message: LocalizedText) -> Self {
+        Notify {
+            message,
+            severity,
+            action: None,
+        }
+    }
+
+    pub fn info(message: LocalizedText) -> Self {
+        Notify::new(Severity::Info, message)
+    }
+
+    pub fn success(message: LocalizedText) -> Self {
+        Notify::new(Severity::Success, message)
+    }
+
+    pub fn warning(message: LocalizedText) -> Self {
+        Notify::new(Severity::Warning, message)
+    }
+
+    pub fn error(message: LocalizedText) -> Self {
+        Notify::new(Severity::Error, message)
+    }
+
+    pub fn with_action(mut self, label: LocalizedText, event: impl Into<AppEvent>) -> Self {
+        self.action = Some(NotifyAction {
+            label,
+            event: event.into(),
+        });
+        self
+    }
+}
+
+/// A `Notify` formatted in the locale active when it arrived.
+struct Toast {
+    message: String,
+    severity: Severity,
+    action: Option<(String, AppEvent)>,
+    remaining: f32,
+}
+
+#[derive(Resource, Default)]
+struct Toasts(Vec<Toast>);
+
+/// Renders `Notify` events as stacked, auto-dismissing toasts in the bottom-right corner.
+pub struct NotifyPlugin;
+
+impl Plugin for NotifyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Toasts>()
+            .add_systems(Update, collect_toasts.in_set(EventsSet::Post))
+            .add_systems(EguiPrimaryContextPass, show_toasts);
+    }
+}
+
+fn collect_toasts(
+    mut toasts: ResMut<Toasts>,
+    mut notify_reader: EventReader<Notify>,
+    localization: Res<Localization>,
+) {
+    for notify in notify_reader.read() {
+        if toasts.0.len() == MAX_TOASTS {
+            toasts.0.remove(0);
+        }
+        toasts.0.push(Toast {
+            message: localization.format(&notify.message),
+            severity: notify.severity,
+            action: notify
+                .action
+                .as_ref()
+                .map(|action| (localization.format(&action.label), action.event.clone())),
+            remaining: notify.severity.duration(),
+        });
+    }
+}
+
+fn show_toasts(
+    mut contexts: EguiContexts,
+    mut toasts: ResMut<Toasts>,
+    mut event_writer: AppEventWriter,
+    time: Res<Time>,
+) {
+    if toasts.0.is_empty() {
+        return;
+    }
+
+    let mut dismissed = Vec::new();
+    let mut dispatched = None;
+
+    egui::Area::new(egui::Id::new("Toasts"))
+        .anchor(Align2::RIGHT_BOTTOM, egui::Vec2::new(-16.0, -16.0))
+        .order(egui::Order::Foreground)
+        .show(contexts.ctx_mut().unwrap(), |ui| {
+            for (index, toast) in toasts.0.iter().enumerate() {
+                Frame::new()
+                    .fill(toast.severity.color())
+                    .corner_radius(8.0)
+                    .inner_margin(Margin::same(10))
+                    .show(ui, |ui| {
+                        ui.set_max_width(320.0);
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(&toast.message).color(Color32::WHITE));
+                            if ui.small_button("×").clicked() {
+                                dismissed.push(index);
+                            }
+                        });
+
+                        if let Some((label, event)) = &toast.action
+                            && ui.button(label).clicked()
+                        {
+                            dispatched = Some(event.clone());
+                            dismissed.push(index);
+                        }
+                    });
+                ui.add_space(6.0);
+            }
+        });
+
+    let delta = time.delta_secs();
+    let mut index = 0;
+    toasts.0.retain_mut(|toast| {
+        toast.remaining -= delta;
+        let keep = toast.remaining > 0.0 && !dismissed.contains(&index);
+        index += 1;
+        keep
+    });
+
+    if let Some(event) = dispatched {
+        event_writer.write(event, EventSource::Toast);
+    }
+}
@@ -0,0 +1,385 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use bevy::color::Color;
+use ron::ser::PrettyConfig;
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, MapAccess, Visitor},
+};
+
+use crate::person_resource::PersonResource;
+
+/// Version of the person files written by `save_person`. Bump it whenever `PersonRecord`
+/// changes shape, and add the migration from the previous version to `MIGRATIONS`.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Upgrades a RON document by one version.
+type Migration = fn(&str) -> Result<String, PersistenceError>;
+
+/// `MIGRATIONS[n - 1]` upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize - 1] = [migrate_v1_to_v2];
+
+/// Values of fields removed from the schema by a migration, or not known to this version, as RON,
+/// keyed by field name. Unknown fields of the person are keyed `person.<field>`.
+///
+/// Kept in the file so a later version can still recover them.
+pub type UnknownFields = BTreeMap<String, String>;
+
+#[derive(Debug)]
+pub enum PersistenceError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Io(error) => write!(f, "{error}"),
+            PersistenceError::Parse(error) => write!(f, "invalid person file: {error}"),
+            PersistenceError::Serialize(error) => write!(f, "could not serialize person: {error}"),
+            PersistenceError::UnsupportedVersion(version) => write!(
+                f,
+                "person file version {version} is newer than the supported {SCHEMA_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {}
+
+/// The persisted fields of `PersonResource`, at `SCHEMA_VERSION`. Missing fields get the
+/// defaults of `PersonResource`.
+#[derive(Serialize)]
+pub struct PersonRecord {
+    pub name: String,
+    pub age: u32,
+    pub location: String,
+    pub color: Color,
+    pub counter: u32,
+}
+
+impl Default for PersonRecord {
+    fn default() -> Self {
+        PersonRecord::from(&PersonResource::default())
+    }
+}
+
+impl From<&PersonResource> for PersonRecord {
+    fn from(person: &PersonResource) -> Self {
+        PersonRecord {
+            name: person.name.clone(),
+            age: person.age,
+            location: person.location.clone(),
+            color: person.color,
+            counter: person.counter,
+        }
+    }
+}
+
+impl From<PersonRecord> for PersonResource {
+    fn from(record: PersonRecord) -> Self {
+        PersonResource {
+            name: record.name,
+            age: record.age,
+            location: record.location,
+            color: record.color,
+            counter: record.counter,
+            ..Default::default()
+        }
+    }
+}
+
+/// A person file at `SCHEMA_VERSION`.
+#[derive(Serialize)]
+struct PersonDocument {
+    version: u32,
+    person: PersonRecord,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    unknown: UnknownFields,
+}
+
+/// Reads the fields of a struct, passing known ones to `known` and keeping the RON of the others
+/// in `unknown` under `prefix` and their name.
+fn read_fields<'de, A: MapAccess<'de>>(
+    mut map: A,
+    prefix: &str,
+    unknown: &mut UnknownFields,
+    mut known: impl FnMut(&str, &mut A) -> Result<bool, A::Error>,
+) -> Result<(), A::Error> {
+    while let Some(key) = map.next_key::<String>()? {
+        if known(&key, &mut map)? {
+            continue;
+        }
+        let value: ron::Value = map.next_value()?;
+        let value = ron::to_string(&value).map_err(de::Error::custom)?;
+        unknown.insert(format!("{prefix}{key}"), value);
+    }
+    Ok(())
+}
+
+/// A `PersonRecord` and the fields this version does not know.
+struct PersonFields(PersonRecord, UnknownFields);
+
+impl<'de> Deserialize<'de> for PersonFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PersonVisitor;
+
+        impl<'de> Visitor<'de> for PersonVisitor {
+            type Value = PersonFields;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a person")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<PersonFields, A::Error> {
+                let mut record = PersonRecord::default();
+                let mut unknown = UnknownFields::new();
+                read_fields(map, "person.", &mut unknown, |key, map| {
+                    match key {
+                        "name" => record.name = map.next_value()?,
+                        "age" => record.age = map.next_value()?,
+                        "location" => record.location = map.next_value()?,
+                        "color" => record.color = map.next_value()?,
+                        "counter" => record.counter = map.next_value()?,
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Ok(PersonFields(record, unknown))
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "PersonRecord",
+            &["name", "age", "location", "color", "counter"],
+            PersonVisitor,
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for PersonDocument {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DocumentVisitor;
+
+        impl<'de> Visitor<'de> for DocumentVisitor {
+            type Value = PersonDocument;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a person document")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<PersonDocument, A::Error> {
+                let mut version = None;
+                let mut person = PersonFields(PersonRecord::default(), UnknownFields::new());
+                let mut unknown = UnknownFields::new();
+                let mut listed = UnknownFields::new();
+                read_fields(map, "", &mut unknown, |key, map| {
+                    match key {
+                        "version" => version = Some(map.next_value()?),
+                        "person" => person = map.next_value()?,
+                        "unknown" => listed = map.next_value()?,
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                let PersonFields(person, person_unknown) = person;
+                unknown.extend(person_unknown);
+                unknown.extend(listed);
+                Ok(PersonDocument {
+                    version: version.ok_or_else(|| de::Error::missing_field("version"))?,
+                    person,
+                    unknown,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "PersonDocument",
+            &["version", "person", "unknown"],
+            DocumentVisitor,
+        )
+    }
+}
+
+impl PersonDocument {
+    fn to_ron(&self) -> Result<String, PersistenceError> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(PersistenceError::Serialize)
+    }
+}
+
+/// Reads only the version of a document of any version.
+#[derive(Deserialize)]
+struct VersionProbe {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Version 1: a bare `PersonResource`, written before files carried a version.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PersonV1 {
+    name: String,
+    age: u32,
+    location: String,
+    color: Color,
+    counter: u32,
+    revision: u64,
+}
+
+/// Wraps the person in a `PersonDocument`. `revision` is runtime state and no longer saved.
+fn migrate_v1_to_v2(source: &str) -> Result<String, PersistenceError> {
+    let person: PersonV1 = ron::from_str(source).map_err(PersistenceError::Parse)?;
+
+    let mut unknown = UnknownFields::new();
+    if person.revision != 0 {
+        unknown.insert("revision".to_string(), person.revision.to_string());
+    }
+
+    PersonDocument {
+        version: 2,
+        person: PersonRecord {
+            name: person.name,
+            age: person.age,
+            location: person.location,
+            color: person.color,
+            counter: person.counter,
+        },
+        unknown,
+    }
+    .to_ron()
+}
+
+/// Upgrades a document of any supported version to `SCHEMA_VERSION`, one migration at a time.
+fn migrate(mut source: String) -> Result<String, PersistenceError> {
+    let probe: VersionProbe = ron::from_str(&source).map_err(PersistenceError::Parse)?;
+    let mut version = probe.version.max(1);
+    if version > SCHEMA_VERSION {
+        return Err(PersistenceError::UnsupportedVersion(version));
+    }
+
+    while version < SCHEMA_VERSION {
+        source = MIGRATIONS[version as usize - 1](&source)?;
+        version += 1;
+    }
+    Ok(source)
+}
+
+pub fn load_person(
+    path: impl AsRef<Path>,
+) -> Result<(PersonResource, UnknownFields), PersistenceError> {
+    let source = fs::read_to_string(path).map_err(PersistenceError::Io)?;
+    let document: PersonDocument =
+        ron::from_str(&migrate(source)?).map_err(PersistenceError::Parse)?;
+    Ok((document.person.into(), document.unknown))
+}
+
+pub fn save_person(
+    path: impl AsRef<Path>,
+    person: &PersonResource,
+    unknown: &UnknownFields,
+) -> Result<(), PersistenceError> {
+    let source = PersonDocument {
+        version: SCHEMA_VERSION,
+        person: person.into(),
+        unknown: unknown.clone(),
+    }
+    .to_ron()?;
+    fs::write(path, source).map_err(PersistenceError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::color::Srgba;
+
+    use super::*;
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    /// A file in the temp directory, unique to this test run.
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn every_historical_version_upgrades() {
+        for version in 1..=SCHEMA_VERSION {
+            let source = fs::read_to_string(fixture(&format!("person_v{version}.ron"))).unwrap();
+            let document: PersonDocument = ron::from_str(&migrate(source).unwrap()).unwrap();
+            assert_eq!(document.version, SCHEMA_VERSION, "fixture v{version}");
+        }
+    }
+
+    #[test]
+    fn v1_keeps_values_and_revision() {
+        let (person, unknown) = load_person(fixture("person_v1.ron")).unwrap();
+        assert_eq!(person.name, "Ada");
+        assert_eq!(person.age, 36);
+        assert_eq!(person.color, Color::Srgba(Srgba::new(1.0, 0.5, 0.25, 1.0)));
+        assert_eq!(person.location, PersonResource::default().location);
+        assert_eq!(person.counter, PersonResource::default().counter);
+        // Runtime state starts over.
+        assert_eq!(person.revision, 0);
+        assert_eq!(unknown.get("revision").map(String::as_str), Some("42"));
+    }
+
+    #[test]
+    fn v2_fills_missing_fields_and_keeps_unknown_ones() {
+        let (person, unknown) = load_person(fixture("person_v2.ron")).unwrap();
+        assert_eq!(person.name, "Grace");
+        assert_eq!(person.age, 45);
+        assert_eq!(person.location, "Arlington");
+        assert_eq!(person.counter, 7);
+        assert_eq!(person.color, PersonResource::default().color);
+        assert_eq!(unknown.get("revision").map(String::as_str), Some("3"));
+        assert_eq!(
+            unknown.get("person.nickname").map(String::as_str),
+            Some("\"Amazing Grace\"")
+        );
+
+        let path = temp_file("person_v2.ron");
+        save_person(&path, &person, &unknown).unwrap();
+        let reloaded = load_person(&path);
+        fs::remove_file(&path).unwrap();
+
+        let (_, reloaded_unknown) = reloaded.unwrap();
+        assert_eq!(reloaded_unknown, unknown);
+    }
+
+    #[test]
+    fn unknown_fields_survive_saving() {
+        let (person, unknown) = load_person(fixture("person_v1.ron")).unwrap();
+        let path = temp_file("person.ron");
+        save_person(&path, &person, &unknown).unwrap();
+        let reloaded = load_person(&path);
+        fs::remove_file(&path).unwrap();
+
+        let (reloaded, reloaded_unknown) = reloaded.unwrap();
+        assert!(reloaded == person);
+        assert_eq!(reloaded_unknown, unknown);
+    }
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let path = temp_file("person_newer.ron");
+        fs::write(
+            &path,
+            format!("(version: {}, person: ())", SCHEMA_VERSION + 1),
+        )
+        .unwrap();
+        let loaded = load_person(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            loaded,
+            Err(PersistenceError::UnsupportedVersion(version)) if version == SCHEMA_VERSION + 1
+        ));
+    }
+}
@@ -1,11 +1,91 @@
+use std::fmt;
+
 use bevy::color::Color;
 use bevy::prelude::Resource;
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct PersonResource {
     pub name: String,
     pub age: u32,
     pub location: String,
     pub color: Color,
     pub counter: u32,
+    /// Bumped by `PersonEditor` on every change, so events can detect that their snapshot is
+    /// outdated.
+    pub revision: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PersonField {
+    Name,
+    Age,
+    Location,
+    Color,
+    Counter,
+}
+
+impl PersonField {
+    pub const ALL: [PersonField; 5] = [
+        PersonField::Name,
+        PersonField::Age,
+        PersonField::Location,
+        PersonField::Color,
+        PersonField::Counter,
+    ];
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PersonValue {
+    Text(String),
+    Number(u32),
+    Color(Color),
+}
+
+impl fmt::Display for PersonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersonValue::Text(text) => write!(f, "{text}"),
+            PersonValue::Number(number) => write!(f, "{number}"),
+            PersonValue::Color(color) => write!(f, "{}", color.to_srgba().to_hex()),
+        }
+    }
+}
+
+impl PersonResource {
+    pub fn get(&self, field: PersonField) -> PersonValue {
+        match field {
+            PersonField::Name => PersonValue::Text(self.name.clone()),
+            PersonField::Age => PersonValue::Number(self.age),
+            PersonField::Location => PersonValue::Text(self.location.clone()),
+            PersonField::Color => PersonValue::Color(self.color),
+            PersonField::Counter => PersonValue::Number(self.counter),
+        }
+    }
+
+    /// Sets `field`, ignoring values of the wrong type.
+    pub fn set(&mut self, field: PersonField, value: PersonValue) {
+        match (field, value) {
+            (PersonField::Name, PersonValue::Text(name)) => self.name = name,
+            (PersonField::Age, PersonValue::Number(age)) => self.age = age,
+            (PersonField::Location, PersonValue::Text(location)) => self.location = location,
+            (PersonField::Color, PersonValue::Color(color)) => self.color = color,
+            (PersonField::Counter, PersonValue::Number(counter)) => self.counter = counter,
+            _ => {}
+        }
+    }
+
+    /// Fields whose value differs between `self` and `other`.
+    pub fn diff(&self, other: &PersonResource) -> Vec<PersonField> {
+        PersonField::ALL
+            .into_iter()
+            .filter(|field| self.get(*field) != other.get(*field))
+            .collect()
+    }
 }
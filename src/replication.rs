@@ -0,0 +1,290 @@
+use std::{collections::HashMap, net::TcpStream};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{
+        envelope::EventEnvelope,
+        events::{AppEvent, AppEventKind},
+        person_changed::{ChangeOrigin, PersonChanged, PersonEditor},
+        prefill::DialogSubmitted,
+        presence::{DialogOpen, EditorPresence},
+        sets::EventsSet,
+        transaction::TransactionId,
+    },
+    json_lines::JsonLineStream,
+    person_resource::{PersonField, PersonValue},
+};
+
+/// Sent by an instance to the relay, which forwards it to every instance including the sender.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplicationMessage {
+    /// Field values written by one routed event, with its dialog result.
+    Edit {
+        user: String,
+        #[serde(flatten)]
+        event: EventEnvelope<AppEvent>,
+        input: Option<String>,
+        changes: Vec<(PersonField, PersonValue)>,
+    },
+    /// Dialogs the user currently has open.
+    Presence {
+        user: String,
+        editing: Vec<AppEventKind>,
+    },
+}
+
+/// Sent by the relay, see `src/bin/relay.rs`.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RelayFrame {
+    Welcome {
+        client: u64,
+    },
+    Message {
+        seq: u64,
+        client: u64,
+        message: ReplicationMessage,
+    },
+    Left {
+        seq: u64,
+        client: u64,
+    },
+}
+
+#[derive(Resource)]
+struct Replication {
+    stream: JsonLineStream,
+    user: String,
+    client: Option<u64>,
+    last_seq: u64,
+    /// Local edits sent to the relay but not echoed back yet. The relay orders them after any
+    /// remote edit still in flight, so remote writes to these fields already lost.
+    unacked: HashMap<PersonField, usize>,
+    users: HashMap<u64, String>,
+    inputs: HashMap<AppEventKind, String>,
+    editing: Vec<AppEventKind>,
+}
+
+impl Replication {
+    /// Whether the frame numbered `seq` is new; the relay may replay frames after a reconnect.
+    fn accept(&mut self, seq: u64) -> bool {
+        if seq <= self.last_seq {
+            return false;
+        }
+        self.last_seq = seq;
+        true
+    }
+}
+
+/// Shares edits with other instances through the relay binary. Every instance applies edits in
+/// the relay's order, so conflicting edits resolve to the last writer per field.
+pub struct ReplicationPlugin {
+    pub address: String,
+    pub user: String,
+}
+
+impl ReplicationPlugin {
+    /// Reads `RELAY_ADDRESS` and `RELAY_USER` (falling back to `USER`); `None` without a relay.
+    pub fn from_env() -> Option<Self> {
+        let address = std::env::var("RELAY_ADDRESS").ok()?;
+        let user = std::env::var("RELAY_USER")
+            .or_else(|_| std::env::var("USER"))
+            .unwrap_or_else(|_| "anonymous".to_string());
+        Some(ReplicationPlugin { address, user })
+    }
+}
+
+impl Plugin for ReplicationPlugin {
+    fn build(&self, app: &mut App) {
+        let stream = match TcpStream::connect(&self.address).and_then(JsonLineStream::new) {
+            Ok(stream) => stream,
+            Err(error) => {
+                error!("Could not connect to relay {}: {error}", self.address);
+                return;
+            }
+        };
+        info!("Connected to relay {} as {}", self.address, self.user);
+
+        app.insert_resource(Replication {
+            stream,
+            user: self.user.clone(),
+            client: None,
+            last_seq: 0,
+            unacked: HashMap::new(),
+            users: HashMap::new(),
+            inputs: HashMap::new(),
+            editing: Vec::new(),
+        })
+        .add_systems(Update, receive_relay_frames.in_set(EventsSet::Handle))
+        .add_systems(Update, send_relay_messages.in_set(EventsSet::Post));
+    }
+}
+
+fn receive_relay_frames(
+    mut replication: ResMut<Replication>,
+    mut person: PersonEditor,
+    mut presence: ResMut<EditorPresence>,
+) {
+    let replication = &mut *replication;
+    if replication.stream.is_closed() {
+        return;
+    }
+
+    for line in replication.stream.read_lines() {
+        let frame = match serde_json::from_str::<RelayFrame>(&line) {
+            Ok(frame) => frame,
+            Err(error) => {
+                warn!("Invalid relay frame: {error}");
+                continue;
+            }
+        };
+
+        match frame {
+            RelayFrame::Welcome { client } => replication.client = Some(client),
+            RelayFrame::Left { seq, client } => {
+                if replication.accept(seq)
+                    && let Some(user) = replication.users.remove(&client)
+                {
+                    presence.remove(&user);
+                }
+            }
+            RelayFrame::Message {
+                seq,
+                client,
+                message,
+            } => {
+                if !replication.accept(seq) {
+                    continue;
+                }
+                let own = replication.client == Some(client);
+
+                match message {
+                    ReplicationMessage::Edit { changes, .. } if own => {
+                        for (field, _) in &changes {
+                            if let Some(count) = replication.unacked.get_mut(field) {
+                                *count = count.saturating_sub(1);
+                            }
+                        }
+                    }
+                    ReplicationMessage::Edit {
+                        user,
+                        event,
+                        changes,
+                        ..
+                    } => {
+                        let event = match event.into_event() {
+                            Ok(event) => event,
+                            Err(error) => {
+                                warn!("Ignoring edit from {user}: {error}");
+                                continue;
+                            }
+                        };
+                        let changes: Vec<_> = changes
+                            .into_iter()
+                            .filter(|(field, _)| {
+                                replication
+                                    .unacked
+                                    .get(field)
+                                    .is_none_or(|count| *count == 0)
+                            })
+                            .collect();
+                        person.edit_from(ChangeOrigin::Remote { user }, event, |person| {
+                            for (field, value) in changes {
+                                person.set(field, value);
+                            }
+                        });
+                    }
+                    ReplicationMessage::Presence { .. } if own => {}
+                    ReplicationMessage::Presence { user, editing } => {
+                        replication.users.insert(client, user.clone());
+                        presence.set(user, editing);
+                    }
+                }
+            }
+        }
+    }
+
+    if replication.stream.is_closed() {
+        warn!("Lost connection to the relay, further edits stay local");
+        for user in replication.users.values() {
+            presence.remove(user);
+        }
+    }
+}
+
+fn send_relay_messages(
+    mut replication: ResMut<Replication>,
+    mut changed_reader: EventReader<PersonChanged>,
+    mut submitted_reader: EventReader<DialogSubmitted>,
+    dialogs: Query<&DialogOpen>,
+) {
+    let replication = &mut *replication;
+
+    for submitted in submitted_reader.read() {
+        replication
+            .inputs
+            .insert(submitted.kind, submitted.input.clone());
+    }
+
+    // `PersonEditor` writes all fields of one edit back to back, so consecutive changes with the
+    // same cause, or of the same committed transaction, form one `Edit`.
+    let mut edits: Vec<(
+        AppEvent,
+        Option<TransactionId>,
+        Vec<(PersonField, PersonValue)>,
+    )> = Vec::new();
+    for changed in changed_reader
+        .read()
+        .filter(|changed| changed.origin == ChangeOrigin::Local)
+    {
+        match edits.last_mut() {
+            Some((cause, transaction, changes))
+                if *transaction == changed.transaction
+                    && (transaction.is_some() || cause.kind() == changed.cause.kind()) =>
+            {
+                changes.push((changed.field, changed.new.clone()));
+            }
+            _ => edits.push((
+                changed.cause.clone(),
+                changed.transaction,
+                vec![(changed.field, changed.new.clone())],
+            )),
+        }
+    }
+
+    for (event, _, changes) in edits {
+        for (field, _) in &changes {
+            *replication.unacked.entry(*field).or_default() += 1;
+        }
+        let input = replication.inputs.remove(&event.kind());
+        replication.stream.send(&ReplicationMessage::Edit {
+            user: replication.user.clone(),
+            event: EventEnvelope::new(event),
+            input,
+            changes,
+        });
+    }
+
+    let mut editing = Vec::new();
+    for dialog in &dialogs {
+        if !editing.contains(&dialog.0) {
+            editing.push(dialog.0);
+        }
+    }
+    if editing.len() != replication.editing.len()
+        || editing
+            .iter()
+            .any(|kind| !replication.editing.contains(kind))
+    {
+        replication.stream.send(&ReplicationMessage::Presence {
+            user: replication.user.clone(),
+            editing: editing.clone(),
+        });
+        replication.editing = editing;
+    }
+
+    replication.stream.flush();
+}
@@ -0,0 +1,468 @@
+use std::{collections::VecDeque, fmt, fs, io, path::Path};
+
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts, EguiPrimaryContextPass,
+    egui::{self, Color32, RichText},
+};
+use serde::Deserialize;
+
+use crate::{
+    events::{
+        events::{AppEventKind, EventRouted},
+        person_changed::{PersonChanged, PersonView},
+        prefill::PrefilledInputs,
+        sets::EventsSet,
+        source::{AppEventWriter, EventSource},
+    },
+    localization::{Localization, LocalizedText},
+    notify::Notify,
+    person_resource::{PersonField, PersonResource, PersonValue},
+};
+
+/// Rule definitions, a RON list of `Rule`s.
+pub const RULES_PATH: &str = "assets/rules.ron";
+
+/// A test on one field of `PersonResource`.
+#[derive(Clone, Debug, Deserialize)]
+pub enum Condition {
+    /// The field is a number greater than the value.
+    Above(PersonField, u32),
+    /// The field is a number less than the value.
+    Below(PersonField, u32),
+    Equals(PersonField, PersonValue),
+}
+
+impl Condition {
+    pub fn field(&self) -> PersonField {
+        match self {
+            Condition::Above(field, _)
+            | Condition::Below(field, _)
+            | Condition::Equals(field, _) => *field,
+        }
+    }
+
+    pub fn holds(&self, value: &PersonValue) -> bool {
+        match (self, value) {
+            (Condition::Above(_, limit), PersonValue::Number(number)) => number > limit,
+            (Condition::Below(_, limit), PersonValue::Number(number)) => number < limit,
+            (Condition::Equals(_, expected), value) => value == expected,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Above(field, limit) => write!(f, "{field:?} > {limit}"),
+            Condition::Below(field, limit) => write!(f, "{field:?} < {limit}"),
+            Condition::Equals(field, value) => write!(f, "{field:?} = {value}"),
+        }
+    }
+}
+
+/// Dispatches `then` whenever `when` starts to hold after a change to its field.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub when: Condition,
+    pub then: AppEventKind,
+    /// Submitted as the dialog result, like a script argument.
+    #[serde(default)]
+    pub input: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum RulesError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    /// `Above` or `Below` on a field that is not a number.
+    NotANumber {
+        rule: String,
+        field: PersonField,
+    },
+    DuplicateName(String),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::Io(error) => write!(f, "{error}"),
+            RulesError::Parse(error) => write!(f, "invalid rules file: {error}"),
+            RulesError::NotANumber { rule, field } => {
+                write!(f, "rule `{rule}` compares {field:?}, which is not a number")
+            }
+            RulesError::DuplicateName(name) => write!(f, "more than one rule is named `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+struct RuleState {
+    rule: Rule,
+    enabled: bool,
+    fired: u32,
+    /// The rule names of the cycle that stopped this rule.
+    cycle: Option<String>,
+}
+
+/// Loaded rules with their state, evaluated against the person changes of every frame.
+#[derive(Resource, Default)]
+pub struct RuleEngine {
+    rules: Vec<RuleState>,
+    /// Indices of the rules fired by the current chain of rule-dispatched events, in order.
+    cascade: Vec<usize>,
+}
+
+impl RuleEngine {
+    pub fn new(rules: Vec<Rule>) -> Result<Self, RulesError> {
+        for (index, rule) in rules.iter().enumerate() {
+            if let Condition::Above(field, _) | Condition::Below(field, _) = rule.when
+                && !matches!(PersonResource::default().get(field), PersonValue::Number(_))
+            {
+                return Err(RulesError::NotANumber {
+                    rule: rule.name.clone(),
+                    field,
+                });
+            }
+            if rules[..index].iter().any(|other| other.name == rule.name) {
+                return Err(RulesError::DuplicateName(rule.name.clone()));
+            }
+        }
+
+        let cycles: Vec<Option<String>> = (0..rules.len())
+            .map(|index| trigger_cycle(&rules, index))
+            .collect();
+        Ok(RuleEngine {
+            rules: rules
+                .into_iter()
+                .zip(cycles)
+                .map(|(rule, cycle)| {
+                    if let Some(cycle) = &cycle {
+                        warn!("Rule cycle {cycle}, disabling `{}`", rule.name);
+                    }
+                    RuleState {
+                        rule,
+                        enabled: cycle.is_none(),
+                        fired: 0,
+                        cycle,
+                    }
+                })
+                .collect(),
+            cascade: Vec::new(),
+        })
+    }
+
+    /// Loads the rules at `path`, or none if the file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RulesError> {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(RulesError::Io(error)),
+        };
+        Self::new(ron::from_str(&source).map_err(RulesError::Parse)?)
+    }
+
+    /// Enables or disables the rule named `name`. Enabling clears a detected cycle.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(state) = self.rules.iter_mut().find(|state| state.rule.name == name) {
+            state.enabled = enabled;
+            if enabled {
+                state.cycle = None;
+            }
+        }
+    }
+
+    /// The first rule in `cascade` that `index` would trigger again, as `a → b → a`.
+    fn cycle(&self, index: usize) -> Option<String> {
+        let start = self.cascade.iter().position(|fired| *fired == index)?;
+        let names: Vec<&str> = self.cascade[start..]
+            .iter()
+            .chain([&index])
+            .map(|fired| self.rules[*fired].rule.name.as_str())
+            .collect();
+        Some(names.join(" → "))
+    }
+}
+
+/// The shortest chain of rules through which `start` could trigger itself, as `a → b → a`.
+///
+/// A rule triggers another when its event writes the field the other rule watches.
+fn trigger_cycle(rules: &[Rule], start: usize) -> Option<String> {
+    let triggers = |from: usize| {
+        (0..rules.len()).filter(move |to| {
+            rules[from]
+                .then
+                .info()
+                .writes
+                .contains(&rules[*to].when.field())
+        })
+    };
+
+    let mut previous = vec![None; rules.len()];
+    let mut queue = VecDeque::from([start]);
+    while let Some(from) = queue.pop_front() {
+        for to in triggers(from) {
+            if to == start {
+                let mut chain = vec![from];
+                let mut at = from;
+                while at != start {
+                    at = previous[at].unwrap();
+                    chain.push(at);
+                }
+                let names: Vec<&str> = chain
+                    .iter()
+                    .rev()
+                    .chain([&start])
+                    .map(|index| rules[*index].name.as_str())
+                    .collect();
+                return Some(names.join(" → "));
+            }
+            if previous[to].is_none() {
+                previous[to] = Some(from);
+                queue.push_back(to);
+            }
+        }
+    }
+    None
+}
+
+/// Evaluates the `RuleEngine` after the handlers of each frame, loaded from `RULES_PATH`.
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        let engine = RuleEngine::load(RULES_PATH).unwrap_or_else(|error| {
+            warn!("Could not load {RULES_PATH}: {error}");
+            RuleEngine::default()
+        });
+
+        app.insert_resource(engine)
+            .add_systems(Startup, notify_rule_cycles)
+            .add_systems(Update, evaluate_rules.in_set(EventsSet::Post));
+    }
+}
+
+/// Shows the rules disabled at load because they could trigger themselves.
+fn notify_rule_cycles(engine: Res<RuleEngine>, mut notify_writer: EventWriter<Notify>) {
+    for state in &engine.rules {
+        if let Some(cycle) = &state.cycle {
+            notify_writer.write(Notify::warning(
+                LocalizedText::new("notify-rule-cycle")
+                    .with_arg("rule", state.rule.name.clone())
+                    .with_arg("cycle", cycle.clone()),
+            ));
+        }
+    }
+}
+
+/// Fires rules whose condition went from false to true in this frame's `PersonChanged` events.
+///
+/// A rule that would fire again within the same chain of rule-dispatched events is part of a
+/// cycle, so it is disabled instead and reported.
+fn evaluate_rules(
+    mut engine: ResMut<RuleEngine>,
+    mut changed_reader: EventReader<PersonChanged>,
+    mut routed_reader: EventReader<EventRouted>,
+    mut prefilled: ResMut<PrefilledInputs>,
+    mut event_writer: AppEventWriter,
+    mut notify_writer: EventWriter<Notify>,
+    person: PersonView,
+) {
+    // Rule events are routed the frame after they fire, so a frame without one ends the chain.
+    let chained = routed_reader
+        .read()
+        .any(|routed| matches!(routed.source, EventSource::Rule { .. }));
+    if !chained {
+        engine.cascade.clear();
+    }
+
+    let mut changes: Vec<(PersonField, &PersonValue, &PersonValue)> = Vec::new();
+    for change in changed_reader.read() {
+        match changes
+            .iter_mut()
+            .find(|(field, ..)| *field == change.field)
+        {
+            Some((_, _, new)) => *new = &change.new,
+            None => changes.push((change.field, &change.old, &change.new)),
+        }
+    }
+
+    for index in 0..engine.rules.len() {
+        let state = &engine.rules[index];
+        let Some((_, old, new)) = changes
+            .iter()
+            .find(|(field, ..)| *field == state.rule.when.field())
+        else {
+            continue;
+        };
+        if !state.enabled || state.rule.when.holds(old) || !state.rule.when.holds(new) {
+            continue;
+        }
+
+        if let Some(cycle) = engine.cycle(index) {
+            warn!("Rule cycle {cycle}, disabling `{}`", state.rule.name);
+            notify_writer.write(Notify::warning(
+                LocalizedText::new("notify-rule-cycle")
+                    .with_arg("rule", state.rule.name.clone())
+                    .with_arg("cycle", cycle.clone()),
+            ));
+            let state = &mut engine.rules[index];
+            state.enabled = false;
+            state.cycle = Some(cycle);
+            continue;
+        }
+
+        let rule = &state.rule;
+        if let Some(input) = &rule.input {
+            prefilled.push(rule.then, input.clone());
+        }
+        event_writer.write(
+            rule.then.construct(&person),
+            EventSource::Rule {
+                name: rule.name.clone(),
+            },
+        );
+
+        engine.rules[index].fired += 1;
+        engine.cascade.push(index);
+    }
+}
+
+#[derive(Resource, Default)]
+struct RulesPanel {
+    error: Option<String>,
+}
+
+/// Egui window listing the loaded rules, with toggles and reloading from `RULES_PATH`.
+pub struct RulesPanelPlugin;
+
+impl Plugin for RulesPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RulesPanel>()
+            .add_systems(EguiPrimaryContextPass, rules_panel);
+    }
+}
+
+fn rules_panel(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<RulesPanel>,
+    mut engine: ResMut<RuleEngine>,
+    localization: Res<Localization>,
+) {
+    let ctx = contexts.ctx_mut().unwrap();
+    egui::Window::new(localization.text("rules-title"))
+        .id(egui::Id::new("Rules Window"))
+        .default_width(320.0)
+        .default_open(false)
+        .show(ctx, |ui| {
+            if ui.button(localization.text("rules-reload")).clicked() {
+                match RuleEngine::load(RULES_PATH) {
+                    Ok(loaded) => {
+                        *engine = loaded;
+                        panel.error = None;
+                    }
+                    Err(error) => panel.error = Some(error.to_string()),
+                }
+            }
+            if let Some(error) = &panel.error {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+            ui.separator();
+
+            if engine.rules.is_empty() {
+                ui.weak(localization.text("rules-empty"));
+            }
+
+            let mut toggled = None;
+            for state in &engine.rules {
+                let mut enabled = state.enabled;
+                if ui.checkbox(&mut enabled, &state.rule.name).changed() {
+                    toggled = Some((state.rule.name.clone(), enabled));
+                }
+
+                let mut then = localization.text(state.rule.then.info().name_id);
+                if let Some(input) = &state.rule.input {
+                    then.push_str(&format!(" '{input}'"));
+                }
+                ui.weak(
+                    localization.format(
+                        &LocalizedText::new("rules-summary")
+                            .with_arg("when", state.rule.when.to_string())
+                            .with_arg("then", then)
+                            .with_arg("fired", state.fired),
+                    ),
+                );
+
+                if let Some(cycle) = &state.cycle {
+                    let text = LocalizedText::new("rules-cycle").with_arg("cycle", cycle.clone());
+                    ui.label(RichText::new(localization.format(&text)).color(Color32::RED));
+                }
+            }
+
+            if let Some((name, enabled)) = toggled {
+                engine.set_enabled(&name, enabled);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, when: Condition, then: AppEventKind) -> Rule {
+        Rule {
+            name: name.to_string(),
+            when,
+            then,
+            input: None,
+        }
+    }
+
+    fn cycles(engine: &RuleEngine) -> Vec<(bool, Option<&str>)> {
+        engine
+            .rules
+            .iter()
+            .map(|state| (state.enabled, state.cycle.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn rules_that_can_trigger_themselves_are_disabled_at_load() {
+        let engine = RuleEngine::new(vec![
+            rule(
+                "old",
+                Condition::Above(PersonField::Age, 60),
+                AppEventKind::ChangeLocation,
+            ),
+            rule(
+                "moved",
+                Condition::Equals(PersonField::Location, PersonValue::Text("Home".into())),
+                AppEventKind::GrowOlder,
+            ),
+            rule(
+                "loop",
+                Condition::Above(PersonField::Counter, 10),
+                AppEventKind::ChangeCounter,
+            ),
+            rule(
+                "red",
+                Condition::Below(PersonField::Counter, 5),
+                AppEventKind::OpenColorPicker,
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cycles(&engine),
+            [
+                (false, Some("old → moved → old")),
+                (false, Some("moved → old → moved")),
+                (false, Some("loop → loop")),
+                (true, None),
+            ]
+        );
+    }
+}
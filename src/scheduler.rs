@@ -0,0 +1,416 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    events::{
+        events::{AppEventKind, EventAvailability},
+        person_changed::PersonView,
+        prefill::PrefilledInputs,
+        sets::EventsSet,
+        source::{AppEventWriter, EventSource},
+    },
+    localization::{Localization, LocalizedText},
+};
+
+/// Relative speeds offered by the Schedule window.
+const SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
+pub type ScheduleId = u64;
+
+/// An `AppEvent` waiting for its time on the `Time<Virtual>` clock.
+#[derive(Clone, Debug)]
+pub struct ScheduledEvent {
+    pub id: ScheduleId,
+    pub kind: AppEventKind,
+    /// Submitted as the dialog result, like a script argument.
+    pub input: Option<String>,
+    /// Virtual elapsed time of the next firing.
+    pub due: Duration,
+    /// Set for recurring events.
+    pub interval: Option<Duration>,
+}
+
+/// One-shot and recurring `AppEvent`s, built from the person when they fire.
+#[derive(Resource, Default)]
+pub struct Scheduler {
+    entries: Vec<ScheduledEvent>,
+    next_id: ScheduleId,
+}
+
+impl Scheduler {
+    /// Fires `kind` when the virtual clock reaches `due`, then every `interval` if given. A zero
+    /// interval fires once.
+    pub fn schedule(
+        &mut self,
+        kind: AppEventKind,
+        input: Option<String>,
+        due: Duration,
+        interval: Option<Duration>,
+    ) -> ScheduleId {
+        self.next_id += 1;
+        self.entries.push(ScheduledEvent {
+            id: self.next_id,
+            kind,
+            input,
+            due,
+            interval: interval.filter(|interval| !interval.is_zero()),
+        });
+        self.next_id
+    }
+
+    /// Returns `false` if `id` already fired or was cancelled.
+    pub fn cancel(&mut self, id: ScheduleId) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != len
+    }
+
+    /// Calls `fire` with every entry due at `now`. Fired one-shot entries are removed and
+    /// recurring ones move to their next occurrence. When `fire` returns `false`, a one-shot entry
+    /// stays pending and a recurring one skips the occurrence.
+    fn fire_due(&mut self, now: Duration, mut fire: impl FnMut(&ScheduledEvent) -> bool) {
+        self.entries.retain_mut(|entry| {
+            if entry.due > now {
+                return true;
+            }
+
+            let fired = fire(entry);
+            let Some(interval) = entry.interval else {
+                return !fired;
+            };
+            entry.due += interval;
+            // Occurrences missed during a long frame are skipped rather than fired in a burst.
+            if entry.due <= now {
+                entry.due = now + interval;
+            }
+            true
+        });
+    }
+
+    /// Pending entries, soonest first.
+    pub fn entries(&self) -> Vec<&ScheduledEvent> {
+        let mut entries: Vec<&ScheduledEvent> = self.entries.iter().collect();
+        entries.sort_by_key(|entry| entry.due);
+        entries
+    }
+}
+
+/// Fires `Scheduler` entries on `Time<Virtual>`, so pausing or speeding up the clock applies to
+/// them too. With `year` set, the person grows older once per in-game year.
+pub struct SchedulerPlugin {
+    pub year: Option<Duration>,
+}
+
+impl Default for SchedulerPlugin {
+    fn default() -> Self {
+        SchedulerPlugin {
+            year: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+impl Plugin for SchedulerPlugin {
+    fn build(&self, app: &mut App) {
+        let mut scheduler = Scheduler::default();
+        if let Some(year) = self.year {
+            scheduler.schedule(AppEventKind::GrowOlder, None, year, Some(year));
+        }
+
+        app.insert_resource(scheduler)
+            .add_systems(Update, fire_scheduled_events.before(EventsSet::Route));
+    }
+}
+
+fn fire_scheduled_events(
+    mut scheduler: ResMut<Scheduler>,
+    mut prefilled: ResMut<PrefilledInputs>,
+    mut event_writer: AppEventWriter,
+    person: PersonView,
+    availability: Res<EventAvailability>,
+    time: Res<Time<Virtual>>,
+) {
+    let now = time.elapsed();
+    if scheduler.entries.iter().all(|entry| entry.due > now) {
+        return;
+    }
+
+    scheduler.fire_due(now, |entry| {
+        let event = entry.kind.construct(&person);
+        // Firing into the router would only get the event rejected, e.g. in read-only mode or
+        // once the person reached the age limit.
+        if availability.check(&event).is_err() {
+            return false;
+        }
+
+        if let Some(input) = &entry.input {
+            prefilled.push(entry.kind, input.clone());
+        }
+        event_writer.write(event, EventSource::Schedule);
+        true
+    });
+}
+
+#[derive(Resource)]
+struct SchedulePanel {
+    kind: AppEventKind,
+    input: String,
+    delay: f32,
+    repeat: bool,
+}
+
+impl Default for SchedulePanel {
+    fn default() -> Self {
+        SchedulePanel {
+            kind: AppEventKind::ALL[0],
+            input: String::new(),
+            delay: 5.0,
+            repeat: false,
+        }
+    }
+}
+
+/// Egui window listing pending scheduled events, with controls for the virtual clock.
+pub struct SchedulePanelPlugin;
+
+impl Plugin for SchedulePanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SchedulePanel>()
+            .add_systems(EguiPrimaryContextPass, schedule_panel);
+    }
+}
+
+fn schedule_panel(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<SchedulePanel>,
+    mut scheduler: ResMut<Scheduler>,
+    mut time: ResMut<Time<Virtual>>,
+    localization: Res<Localization>,
+) {
+    let ctx = contexts.ctx_mut().unwrap();
+    egui::Window::new(localization.text("schedule-title"))
+        .id(egui::Id::new("Schedule Window"))
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::Vec2::new(16.0, -16.0))
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            clock_controls(ui, &mut time, &localization);
+            ui.separator();
+            add_controls(
+                ui,
+                &mut panel,
+                &mut scheduler,
+                time.elapsed(),
+                &localization,
+            );
+            ui.separator();
+
+            let now = time.elapsed();
+            let mut cancelled = None;
+            for entry in scheduler.entries() {
+                ui.horizontal(|ui| {
+                    let mut label = localization.text(entry.kind.info().name_id);
+                    if let Some(input) = &entry.input {
+                        label.push_str(&format!(" '{input}'"));
+                    }
+                    ui.label(label);
+
+                    let remaining = format!("{:.1}", entry.due.saturating_sub(now).as_secs_f32());
+                    let when = match entry.interval {
+                        Some(interval) => LocalizedText::new("schedule-every")
+                            .with_arg("interval", format!("{:.1}", interval.as_secs_f32()))
+                            .with_arg("remaining", remaining),
+                        None => {
+                            LocalizedText::new("schedule-once").with_arg("remaining", remaining)
+                        }
+                    };
+                    ui.weak(localization.format(&when));
+
+                    if ui
+                        .small_button(localization.text("button-cancel"))
+                        .clicked()
+                    {
+                        cancelled = Some(entry.id);
+                    }
+                });
+            }
+            if scheduler.entries.is_empty() {
+                ui.weak(localization.text("schedule-empty"));
+            }
+
+            if let Some(id) = cancelled {
+                scheduler.cancel(id);
+            }
+        });
+}
+
+fn clock_controls(ui: &mut egui::Ui, time: &mut Time<Virtual>, localization: &Localization) {
+    ui.horizontal(|ui| {
+        ui.label(
+            localization.format(
+                &LocalizedText::new("schedule-time")
+                    .with_arg("time", format!("{:.1}", time.elapsed_secs())),
+            ),
+        );
+
+        if time.is_paused() {
+            if ui.button(localization.text("schedule-resume")).clicked() {
+                time.unpause();
+            }
+        } else if ui.button(localization.text("schedule-pause")).clicked() {
+            time.pause();
+        }
+
+        let mut speed = time.relative_speed();
+        for available in SPEEDS {
+            ui.selectable_value(&mut speed, available, format!("{available}×"));
+        }
+        if speed != time.relative_speed() {
+            time.set_relative_speed(speed);
+        }
+    });
+}
+
+fn add_controls(
+    ui: &mut egui::Ui,
+    panel: &mut SchedulePanel,
+    scheduler: &mut Scheduler,
+    now: Duration,
+    localization: &Localization,
+) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("Scheduled Event")
+            .selected_text(localization.text(panel.kind.info().name_id))
+            .show_ui(ui, |ui| {
+                for kind in AppEventKind::ALL {
+                    ui.selectable_value(
+                        &mut panel.kind,
+                        *kind,
+                        localization.text(kind.info().name_id),
+                    );
+                }
+            });
+
+        if panel.kind.info().needs_dialog {
+            ui.add(
+                egui::TextEdit::singleline(&mut panel.input)
+                    .hint_text(localization.text("schedule-input"))
+                    .desired_width(80.0),
+            );
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(localization.text("schedule-delay"));
+        ui.add(
+            egui::DragValue::new(&mut panel.delay)
+                .range(0.1..=3600.0)
+                .suffix("s"),
+        );
+        ui.checkbox(&mut panel.repeat, localization.text("schedule-repeat"));
+
+        if ui.button(localization.text("schedule-add")).clicked() {
+            let delay = Duration::from_secs_f32(panel.delay);
+            // Without an input, a dialog event opens its dialog when it fires.
+            let input = Some(panel.input.clone())
+                .filter(|input| panel.kind.info().needs_dialog && !input.is_empty());
+            scheduler.schedule(
+                panel.kind,
+                input,
+                now + delay,
+                panel.repeat.then_some(delay),
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    /// Fires everything due at `now`, returning the kinds that fired.
+    fn fire(scheduler: &mut Scheduler, now: Duration) -> Vec<AppEventKind> {
+        let mut fired = Vec::new();
+        scheduler.fire_due(now, |entry| {
+            fired.push(entry.kind);
+            true
+        });
+        fired
+    }
+
+    fn due(scheduler: &Scheduler) -> Vec<Duration> {
+        scheduler.entries().iter().map(|entry| entry.due).collect()
+    }
+
+    #[test]
+    fn one_shot_events_fire_once_and_are_removed() {
+        let mut scheduler = Scheduler::default();
+        scheduler.schedule(AppEventKind::GrowOlder, None, 2 * SECOND, None);
+
+        assert!(fire(&mut scheduler, SECOND).is_empty());
+        assert_eq!(fire(&mut scheduler, 2 * SECOND), [AppEventKind::GrowOlder]);
+        assert!(scheduler.entries().is_empty());
+        assert!(fire(&mut scheduler, 3 * SECOND).is_empty());
+    }
+
+    #[test]
+    fn recurring_events_are_rescheduled() {
+        let mut scheduler = Scheduler::default();
+        scheduler.schedule(AppEventKind::GrowOlder, None, SECOND, Some(SECOND));
+        // A zero interval fires once.
+        scheduler.schedule(
+            AppEventKind::ChangeCounter,
+            None,
+            SECOND,
+            Some(Duration::ZERO),
+        );
+
+        assert_eq!(
+            fire(&mut scheduler, SECOND),
+            [AppEventKind::GrowOlder, AppEventKind::ChangeCounter]
+        );
+        assert_eq!(due(&scheduler), [2 * SECOND]);
+        assert_eq!(fire(&mut scheduler, 2 * SECOND), [AppEventKind::GrowOlder]);
+        assert_eq!(due(&scheduler), [3 * SECOND]);
+    }
+
+    #[test]
+    fn cancelled_events_do_not_fire() {
+        let mut scheduler = Scheduler::default();
+        let once = scheduler.schedule(AppEventKind::GrowOlder, None, SECOND, None);
+        let recurring = scheduler.schedule(AppEventKind::ChangeCounter, None, SECOND, Some(SECOND));
+
+        assert!(scheduler.cancel(once));
+        assert!(scheduler.cancel(recurring));
+        assert!(!scheduler.cancel(once));
+        assert!(fire(&mut scheduler, 5 * SECOND).is_empty());
+    }
+
+    #[test]
+    fn missed_occurrences_are_skipped() {
+        let mut scheduler = Scheduler::default();
+        scheduler.schedule(AppEventKind::GrowOlder, None, SECOND, Some(SECOND));
+
+        // A long frame jumps past several occurrences, which fire once in total.
+        let now = Duration::from_millis(4500);
+        assert_eq!(fire(&mut scheduler, now), [AppEventKind::GrowOlder]);
+        assert_eq!(due(&scheduler), [now + SECOND]);
+    }
+
+    #[test]
+    fn refused_events_wait_or_skip_the_occurrence() {
+        let mut scheduler = Scheduler::default();
+        scheduler.schedule(AppEventKind::ChangeName, None, SECOND, None);
+        scheduler.schedule(AppEventKind::GrowOlder, None, SECOND, Some(SECOND));
+
+        scheduler.fire_due(SECOND, |_| false);
+        assert_eq!(due(&scheduler), [SECOND, 2 * SECOND]);
+        assert_eq!(
+            fire(&mut scheduler, 2 * SECOND),
+            [AppEventKind::ChangeName, AppEventKind::GrowOlder]
+        );
+        assert_eq!(due(&scheduler), [3 * SECOND]);
+    }
+}
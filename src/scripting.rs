@@ -0,0 +1,491 @@
+use std::{collections::VecDeque, fmt, str::FromStr};
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    events::{
+        events::{AppEventKind, EventRejected, EventRouted},
+        person_changed::PersonView,
+        prefill::{DialogSubmitted, PrefilledInputs},
+        sets::EventsSet,
+        source::{AppEventWriter, EventSource},
+        transaction::TransactionWriter,
+    },
+    localization::Localization,
+};
+
+/// Frames skipped after each dispatched event so its handler runs before the next step.
+const FRAMES_PER_STEP: u32 = 1;
+
+/// One statement of a script: `ChangeName 'Alice'`, `GrowOlder x3`, `wait 10`, or `begin`,
+/// `commit` and `abort` around an edit transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptStep {
+    Event {
+        kind: AppEventKind,
+        input: Option<String>,
+        repeat: u32,
+    },
+    Wait(u32),
+    Begin,
+    Commit,
+    Abort,
+}
+
+/// A sequence of `AppEvent`s with pre-filled dialog results, e.g.
+/// `ChangeName 'Alice'; GrowOlder x3; ChangeLocation 'Berlin'`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Script {
+    pub steps: Vec<ScriptStep>,
+}
+
+enum Token {
+    Word(String),
+    Quoted(String),
+    Separator,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' | '\n' => tokens.push(Token::Separator),
+            '\'' | '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(next) if next == c => break,
+                        Some(next) => text.push(next),
+                        None => return Err(format!("Unterminated string {c}{text}")),
+                    }
+                }
+                tokens.push(Token::Quoted(text));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, ';' | '\'' | '"') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_step(tokens: &[Token]) -> Result<ScriptStep, String> {
+    let Some(Token::Word(name)) = tokens.first() else {
+        return Err("Expected an event name".to_string());
+    };
+
+    if name == "wait" {
+        return match &tokens[1..] {
+            [Token::Word(frames)] => frames
+                .parse()
+                .map(ScriptStep::Wait)
+                .map_err(|_| format!("Invalid frame count `{frames}`")),
+            _ => Err("Expected `wait <frames>`".to_string()),
+        };
+    }
+
+    let transaction_step = match name.as_str() {
+        "begin" => Some(ScriptStep::Begin),
+        "commit" => Some(ScriptStep::Commit),
+        "abort" => Some(ScriptStep::Abort),
+        _ => None,
+    };
+    if let Some(step) = transaction_step {
+        return match tokens.len() {
+            1 => Ok(step),
+            _ => Err(format!("`{name}` takes no arguments")),
+        };
+    }
+
+    let kind: AppEventKind = name.parse()?;
+    let mut input = None;
+    let mut repeat = 1;
+    for token in &tokens[1..] {
+        match token {
+            Token::Quoted(text) if input.is_none() => input = Some(text.clone()),
+            Token::Word(word) if word.starts_with('x') => {
+                repeat = word[1..]
+                    .parse()
+                    .map_err(|_| format!("Invalid repeat count `{word}`"))?;
+            }
+            _ => return Err(format!("Unexpected argument in `{name}` step")),
+        }
+    }
+
+    if input.is_some() && !kind.info().needs_dialog {
+        return Err(format!("`{name}` has no dialog to fill"));
+    }
+
+    Ok(ScriptStep::Event {
+        kind,
+        input,
+        repeat,
+    })
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let steps = tokens
+            .split(|token| matches!(token, Token::Separator))
+            .filter(|statement| !statement.is_empty())
+            .map(parse_step)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Script { steps })
+    }
+}
+
+impl fmt::Display for ScriptStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptStep::Wait(frames) => write!(f, "wait {frames}"),
+            ScriptStep::Begin => write!(f, "begin"),
+            ScriptStep::Commit => write!(f, "commit"),
+            ScriptStep::Abort => write!(f, "abort"),
+            ScriptStep::Event {
+                kind,
+                input,
+                repeat,
+            } => {
+                write!(f, "{kind:?}")?;
+                if let Some(input) = input {
+                    let quote = if input.contains('\'') { '"' } else { '\'' };
+                    write!(f, " {quote}{input}{quote}")?;
+                }
+                if *repeat > 1 {
+                    write!(f, " x{repeat}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Executes scripts one step at a time, queueing dialog results in `PrefilledInputs`.
+#[derive(Resource, Default)]
+pub struct ScriptRunner {
+    queue: VecDeque<ScriptStep>,
+    skip_frames: u32,
+}
+
+impl ScriptRunner {
+    pub fn run(&mut self, script: Script) {
+        self.queue.extend(script.steps);
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.queue.is_empty() && self.skip_frames == 0
+    }
+}
+
+/// Records events routed from buttons, shortcuts and the palette, and their dialog results, into a
+/// replayable `Script`.
+#[derive(Resource, Default)]
+pub struct ScriptRecorder {
+    recording: bool,
+    script: Script,
+}
+
+impl ScriptRecorder {
+    pub fn start(&mut self) {
+        self.recording = true;
+        self.script = Script::default();
+    }
+
+    pub fn stop(&mut self) -> Script {
+        self.recording = false;
+        std::mem::take(&mut self.script)
+    }
+
+    fn push_event(&mut self, kind: AppEventKind) {
+        if let Some(ScriptStep::Event {
+            kind: last_kind,
+            input: None,
+            repeat,
+        }) = self.script.steps.last_mut()
+            && *last_kind == kind
+            && !kind.info().needs_dialog
+        {
+            *repeat += 1;
+            return;
+        }
+
+        self.script.steps.push(ScriptStep::Event {
+            kind,
+            input: None,
+            repeat: 1,
+        });
+    }
+
+    fn fill_input(&mut self, kind: AppEventKind, submitted: String) {
+        let step = self.script.steps.iter_mut().rev().find(|step| {
+            matches!(step, ScriptStep::Event { kind: step_kind, input: None, .. } if *step_kind == kind)
+        });
+
+        if let Some(ScriptStep::Event { input, .. }) = step {
+            *input = Some(submitted);
+        }
+    }
+}
+
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScriptRunner>()
+            .init_resource::<ScriptRecorder>()
+            .add_systems(Update, run_script_steps.before(EventsSet::Route))
+            .add_systems(Update, record_script_steps.in_set(EventsSet::Post));
+    }
+}
+
+fn run_script_steps(
+    mut runner: ResMut<ScriptRunner>,
+    mut prefilled: ResMut<PrefilledInputs>,
+    mut rejected_reader: EventReader<EventRejected>,
+    mut event_writer: AppEventWriter,
+    mut transaction_writer: TransactionWriter,
+    person: PersonView,
+) {
+    // A rejected event never opens its dialog, so its pre-filled result must not leak into the next one.
+    for rejected in rejected_reader.read() {
+        prefilled.take(rejected.event.kind());
+    }
+
+    if runner.skip_frames > 0 {
+        runner.skip_frames -= 1;
+        return;
+    }
+
+    let Some(step) = runner.queue.pop_front() else {
+        return;
+    };
+
+    match step {
+        ScriptStep::Wait(frames) => runner.skip_frames = frames,
+        ScriptStep::Begin => {
+            transaction_writer.begin();
+            runner.skip_frames = FRAMES_PER_STEP;
+        }
+        ScriptStep::Commit => {
+            transaction_writer.commit();
+            runner.skip_frames = FRAMES_PER_STEP;
+        }
+        ScriptStep::Abort => {
+            transaction_writer.abort();
+            runner.skip_frames = FRAMES_PER_STEP;
+        }
+        ScriptStep::Event {
+            kind,
+            input,
+            repeat,
+        } => {
+            if let Some(input) = &input {
+                prefilled.push(kind, input.clone());
+            }
+            event_writer.write(kind.construct(&person), EventSource::Script);
+
+            if repeat > 1 {
+                runner.queue.push_front(ScriptStep::Event {
+                    kind,
+                    input,
+                    repeat: repeat - 1,
+                });
+            }
+            runner.skip_frames = FRAMES_PER_STEP;
+        }
+    }
+}
+
+fn record_script_steps(
+    mut recorder: ResMut<ScriptRecorder>,
+    mut routed_reader: EventReader<EventRouted>,
+    mut submitted_reader: EventReader<DialogSubmitted>,
+) {
+    if !recorder.recording {
+        routed_reader.clear();
+        submitted_reader.clear();
+        return;
+    }
+
+    // Only what the user triggered; events from scripts, rules, the scheduler or the network
+    // would be dispatched again by their own origin on replay.
+    let user_events = routed_reader.read().filter(|routed| {
+        matches!(
+            routed.source,
+            EventSource::Button | EventSource::Shortcut | EventSource::Palette
+        )
+    });
+    for routed in user_events {
+        recorder.push_event(routed.event.kind());
+    }
+    for submitted in submitted_reader.read() {
+        recorder.fill_input(submitted.kind, submitted.input.clone());
+    }
+}
+
+#[derive(Resource, Default)]
+struct ScriptPanel {
+    source: String,
+    error: Option<String>,
+}
+
+/// Egui window to run and record scripts.
+pub struct ScriptPanelPlugin;
+
+impl Plugin for ScriptPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScriptPanel>()
+            .add_systems(EguiPrimaryContextPass, script_panel);
+    }
+}
+
+fn script_panel(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<ScriptPanel>,
+    mut runner: ResMut<ScriptRunner>,
+    mut recorder: ResMut<ScriptRecorder>,
+    localization: Res<Localization>,
+) {
+    let ctx = contexts.ctx_mut().unwrap();
+    egui::Window::new(localization.text("script-title"))
+        .id(egui::Id::new("Script Window"))
+        .anchor(egui::Align2::LEFT_TOP, egui::Vec2::new(16.0, 16.0))
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut panel.source)
+                    .code_editor()
+                    .desired_rows(4)
+                    .hint_text("ChangeName 'Alice'; GrowOlder x3; ChangeLocation 'Berlin'"),
+            );
+
+            if let Some(error) = &panel.error {
+                ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+            }
+
+            ui.horizontal(|ui| {
+                let run = ui.add_enabled(
+                    runner.is_idle() && !recorder.recording,
+                    egui::Button::new(localization.text("script-run")),
+                );
+                if run.clicked() {
+                    match panel.source.parse::<Script>() {
+                        Ok(script) => {
+                            panel.error = None;
+                            runner.run(script);
+                        }
+                        Err(error) => panel.error = Some(error),
+                    }
+                }
+
+                if recorder.recording {
+                    if ui.button(localization.text("script-stop")).clicked() {
+                        panel.source = recorder.stop().to_string();
+                    }
+                } else if ui.button(localization.text("script-record")).clicked() {
+                    recorder.start();
+                }
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_parse_quoted_inputs_and_repeats() {
+        let script: Script =
+            "ChangeName \"Bob's\"; GrowOlder x3\nwait 2; begin; ChangeLocation 'Bad Ems'; commit"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            script.steps,
+            [
+                ScriptStep::Event {
+                    kind: AppEventKind::ChangeName,
+                    input: Some("Bob's".to_string()),
+                    repeat: 1,
+                },
+                ScriptStep::Event {
+                    kind: AppEventKind::GrowOlder,
+                    input: None,
+                    repeat: 3,
+                },
+                ScriptStep::Wait(2),
+                ScriptStep::Begin,
+                ScriptStep::Event {
+                    kind: AppEventKind::ChangeLocation,
+                    input: Some("Bad Ems".to_string()),
+                    repeat: 1,
+                },
+                ScriptStep::Commit,
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_scripts_are_rejected() {
+        for source in [
+            "Teleport",
+            "wait",
+            "wait soon",
+            "ChangeName 'Alice",
+            "GrowOlder 'twice'",
+            "ChangeName 'Alice' 'Bob'",
+            "GrowOlder xmany",
+            "commit now",
+        ] {
+            assert!(source.parse::<Script>().is_err(), "`{source}` parsed");
+        }
+    }
+
+    #[test]
+    fn recorded_scripts_parse_back() {
+        let mut recorder = ScriptRecorder::default();
+        recorder.start();
+        recorder.push_event(AppEventKind::ChangeName);
+        recorder.fill_input(AppEventKind::ChangeName, "O'Brien".to_string());
+        recorder.push_event(AppEventKind::GrowOlder);
+        recorder.push_event(AppEventKind::GrowOlder);
+        recorder.push_event(AppEventKind::ChangeLocation);
+        recorder.fill_input(AppEventKind::ChangeLocation, "Berlin".to_string());
+        let script = recorder.stop();
+
+        assert_eq!(
+            script.to_string(),
+            "ChangeName \"O'Brien\"; GrowOlder x2; ChangeLocation 'Berlin'"
+        );
+        assert_eq!(script.to_string().parse::<Script>(), Ok(script));
+    }
+}
@@ -0,0 +1,424 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use bevy::prelude::*;
+use bevy_egui::input::EguiWantsInput;
+
+use crate::{
+    events::{
+        dialog_backend::DialogWantsInput,
+        events::AppEventKind,
+        person_changed::PersonView,
+        sets::EventsSet,
+        source::{AppEventWriter, EventSource},
+    },
+    localization::LocalizedText,
+    notify::Notify,
+};
+
+/// Overrides for the default shortcuts declared in `create_events_plugin!`.
+///
+/// One binding per line, `<chord> = <event>`, e.g. `Ctrl+Shift+N = ChangeName`. Lines starting with `#` are ignored.
+pub const SHORTCUTS_PATH: &str = "assets/shortcuts.cfg";
+
+/// Chords used by the app itself that events cannot be bound to.
+const RESERVED: [&str; 1] = ["Ctrl+P"];
+
+const KEYS: [(&str, KeyCode); 58] = [
+    ("A", KeyCode::KeyA),
+    ("B", KeyCode::KeyB),
+    ("C", KeyCode::KeyC),
+    ("D", KeyCode::KeyD),
+    ("E", KeyCode::KeyE),
+    ("F", KeyCode::KeyF),
+    ("G", KeyCode::KeyG),
+    ("H", KeyCode::KeyH),
+    ("I", KeyCode::KeyI),
+    ("J", KeyCode::KeyJ),
+    ("K", KeyCode::KeyK),
+    ("L", KeyCode::KeyL),
+    ("M", KeyCode::KeyM),
+    ("N", KeyCode::KeyN),
+    ("O", KeyCode::KeyO),
+    ("P", KeyCode::KeyP),
+    ("Q", KeyCode::KeyQ),
+    ("R", KeyCode::KeyR),
+    ("S", KeyCode::KeyS),
+    ("T", KeyCode::KeyT),
+    ("U", KeyCode::KeyU),
+    ("V", KeyCode::KeyV),
+    ("W", KeyCode::KeyW),
+    ("X", KeyCode::KeyX),
+    ("Y", KeyCode::KeyY),
+    ("Z", KeyCode::KeyZ),
+    ("0", KeyCode::Digit0),
+    ("1", KeyCode::Digit1),
+    ("2", KeyCode::Digit2),
+    ("3", KeyCode::Digit3),
+    ("4", KeyCode::Digit4),
+    ("5", KeyCode::Digit5),
+    ("6", KeyCode::Digit6),
+    ("7", KeyCode::Digit7),
+    ("8", KeyCode::Digit8),
+    ("9", KeyCode::Digit9),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Escape", KeyCode::Escape),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Up", KeyCode::ArrowUp),
+    ("Down", KeyCode::ArrowDown),
+    ("Left", KeyCode::ArrowLeft),
+    ("Right", KeyCode::ArrowRight),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct KeyChord {
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    key: KeyCode,
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chord = KeyChord {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            key: KeyCode::Escape,
+        };
+
+        let mut parts = s.split('+').map(str::trim).peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                chord.key = KEYS
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(part))
+                    .map(|(_, key)| *key)
+                    .ok_or_else(|| format!("Unknown key `{part}` in `{s}`"))?;
+                continue;
+            }
+
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                _ => return Err(format!("Unknown modifier `{part}` in `{s}`")),
+            }
+        }
+
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+
+        match KEYS.iter().find(|(_, key)| *key == self.key) {
+            Some((name, _)) => write!(f, "{name}"),
+            None => write!(f, "{:?}", self.key),
+        }
+    }
+}
+
+/// Two events, or an event and the app, claiming the same chord.
+#[derive(Clone, Debug)]
+pub struct ShortcutConflict {
+    pub chord: KeyChord,
+    pub existing: Option<AppEventKind>,
+    pub rejected: AppEventKind,
+}
+
+impl fmt::Display for ShortcutConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.existing {
+            Some(existing) => write!(
+                f,
+                "{} is bound to {:?}, ignoring binding to {:?}",
+                self.chord, existing, self.rejected
+            ),
+            None => write!(
+                f,
+                "{} is reserved, ignoring binding to {:?}",
+                self.chord, self.rejected
+            ),
+        }
+    }
+}
+
+/// Key chords that dispatch `AppEvent`s, built from registry defaults and `SHORTCUTS_PATH`.
+#[derive(Resource, Default)]
+pub struct Shortcuts {
+    bindings: HashMap<KeyChord, AppEventKind>,
+    conflicts: Vec<ShortcutConflict>,
+}
+
+impl Shortcuts {
+    pub fn from_defaults() -> Self {
+        let mut shortcuts = Shortcuts::default();
+        for kind in AppEventKind::ALL {
+            let Some(shortcut) = kind.info().shortcut else {
+                continue;
+            };
+
+            match shortcut.parse() {
+                Ok(chord) => {
+                    shortcuts.bind(chord, *kind);
+                }
+                Err(error) => warn!("Invalid default shortcut for {kind:?}: {error}"),
+            }
+        }
+        shortcuts
+    }
+
+    /// Applies `<chord> = <event>` overrides, replacing the previous chord of each listed event.
+    ///
+    /// All listed events are unbound before any override is bound, so two events can swap chords.
+    pub fn load_overrides(&mut self, source: &str) {
+        let mut overrides = Vec::new();
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((chord, kind)) = line.split_once('=') else {
+                warn!("Invalid shortcut line `{line}`");
+                continue;
+            };
+
+            let chord = match chord.trim().parse::<KeyChord>() {
+                Ok(chord) => chord,
+                Err(error) => {
+                    warn!("{error}");
+                    continue;
+                }
+            };
+            let Ok(kind) = kind.trim().parse::<AppEventKind>() else {
+                warn!("Unknown event `{}` in shortcut line `{line}`", kind.trim());
+                continue;
+            };
+            overrides.push((chord, kind));
+        }
+
+        let previous = self.bindings.clone();
+        self.bindings
+            .retain(|_, bound| !overrides.iter().any(|(_, kind)| kind == bound));
+
+        let mut rejected = Vec::new();
+        for (chord, kind) in overrides {
+            if self.bind(chord, kind) {
+                self.bindings
+                    .retain(|bound_chord, bound| *bound != kind || *bound_chord == chord);
+            } else {
+                rejected.push(kind);
+            }
+        }
+
+        // A conflicting override keeps the previous chord, unless another override took it.
+        for kind in rejected {
+            if self.chord_for(kind).is_some() {
+                continue;
+            }
+            let chords = previous.iter().filter(|(_, bound)| **bound == kind);
+            for (chord, _) in chords {
+                self.bind(*chord, kind);
+            }
+        }
+    }
+
+    /// Returns `false` and records a conflict if `chord` is reserved or bound to another event.
+    pub fn bind(&mut self, chord: KeyChord, kind: AppEventKind) -> bool {
+        let reserved = RESERVED
+            .iter()
+            .any(|reserved| reserved.parse::<KeyChord>().ok() == Some(chord));
+        let existing = self.bindings.get(&chord).copied();
+
+        if reserved || existing.is_some_and(|existing| existing != kind) {
+            let conflict = ShortcutConflict {
+                chord,
+                existing,
+                rejected: kind,
+            };
+            warn!("Shortcut conflict: {conflict}");
+            self.conflicts.push(conflict);
+            return false;
+        }
+
+        self.bindings.insert(chord, kind);
+        true
+    }
+
+    pub fn chord_for(&self, kind: AppEventKind) -> Option<KeyChord> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| **bound == kind)
+            .map(|(chord, _)| *chord)
+    }
+
+    pub fn conflicts(&self) -> &[ShortcutConflict] {
+        &self.conflicts
+    }
+}
+
+pub struct ShortcutsPlugin;
+
+impl Plugin for ShortcutsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut shortcuts = Shortcuts::from_defaults();
+        if let Ok(source) = std::fs::read_to_string(SHORTCUTS_PATH) {
+            shortcuts.load_overrides(&source);
+        }
+
+        app.insert_resource(shortcuts)
+            .add_systems(Startup, notify_shortcut_conflicts)
+            .add_systems(Update, trigger_shortcuts.before(EventsSet::Route));
+    }
+}
+
+fn notify_shortcut_conflicts(shortcuts: Res<Shortcuts>, mut notify_writer: EventWriter<Notify>) {
+    for conflict in shortcuts.conflicts() {
+        let message = match conflict.existing {
+            Some(existing) => LocalizedText::new("notify-shortcut-conflict")
+                .with_text_arg("existing", LocalizedText::new(existing.info().name_id)),
+            None => LocalizedText::new("notify-shortcut-reserved"),
+        };
+        notify_writer.write(Notify::warning(
+            message
+                .with_arg("chord", conflict.chord.to_string())
+                .with_text_arg(
+                    "event",
+                    LocalizedText::new(conflict.rejected.info().name_id),
+                ),
+        ));
+    }
+}
+
+fn trigger_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    wants_input: Res<EguiWantsInput>,
+    dialog_wants_input: Res<DialogWantsInput>,
+    shortcuts: Res<Shortcuts>,
+    person: PersonView,
+    mut event_writer: AppEventWriter,
+) {
+    if wants_input.wants_any_keyboard_input() || dialog_wants_input.get() {
+        return;
+    }
+
+    for key in keys.get_just_pressed() {
+        let chord = KeyChord {
+            ctrl: keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            shift: keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            alt: keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+            key: *key,
+        };
+
+        if let Some(kind) = shortcuts.bindings.get(&chord) {
+            event_writer.write(kind.construct(&person), EventSource::Shortcut);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(s: &str) -> KeyChord {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn chords_parse_and_display() {
+        for source in ["Ctrl+N", "Ctrl+Shift+Alt+F5", "Space", "Shift+Up"] {
+            assert_eq!(chord(source).to_string(), source);
+        }
+        assert_eq!(chord(" alt + ctrl + k ").to_string(), "Ctrl+Alt+K");
+
+        assert!("Ctrl+Nope".parse::<KeyChord>().is_err());
+        assert!("Meta+N".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn reserved_chords_are_not_bound() {
+        let mut shortcuts = Shortcuts::from_defaults();
+        shortcuts.load_overrides("Ctrl+P = ChangeName");
+
+        assert_eq!(
+            shortcuts.chord_for(AppEventKind::ChangeName),
+            Some(chord("Ctrl+N"))
+        );
+        let [conflict] = shortcuts.conflicts() else {
+            panic!("expected one conflict, got {:?}", shortcuts.conflicts());
+        };
+        assert_eq!(conflict.chord, chord("Ctrl+P"));
+        assert_eq!(conflict.existing, None);
+        assert_eq!(conflict.rejected, AppEventKind::ChangeName);
+    }
+
+    #[test]
+    fn conflicting_override_keeps_previous_chord() {
+        let mut shortcuts = Shortcuts::from_defaults();
+        shortcuts.load_overrides("Ctrl+G = ChangeName");
+
+        assert_eq!(
+            shortcuts.chord_for(AppEventKind::ChangeName),
+            Some(chord("Ctrl+N"))
+        );
+        assert_eq!(
+            shortcuts.chord_for(AppEventKind::GrowOlder),
+            Some(chord("Ctrl+G"))
+        );
+        let [conflict] = shortcuts.conflicts() else {
+            panic!("expected one conflict, got {:?}", shortcuts.conflicts());
+        };
+        assert_eq!(conflict.existing, Some(AppEventKind::GrowOlder));
+        assert_eq!(conflict.rejected, AppEventKind::ChangeName);
+    }
+
+    #[test]
+    fn overrides_can_swap_chords() {
+        let mut shortcuts = Shortcuts::from_defaults();
+        shortcuts.load_overrides(
+            "# Swap name and age\nCtrl+G = ChangeName\nCtrl+N = GrowOlder\nCtrl+Shift+L = ChangeLocation\n",
+        );
+
+        assert!(shortcuts.conflicts().is_empty());
+        assert_eq!(
+            shortcuts.chord_for(AppEventKind::ChangeName),
+            Some(chord("Ctrl+G"))
+        );
+        assert_eq!(
+            shortcuts.chord_for(AppEventKind::GrowOlder),
+            Some(chord("Ctrl+N"))
+        );
+        assert_eq!(
+            shortcuts.chord_for(AppEventKind::ChangeLocation),
+            Some(chord("Ctrl+Shift+L"))
+        );
+        assert!(!shortcuts.bindings.contains_key(&chord("Ctrl+L")));
+    }
+}